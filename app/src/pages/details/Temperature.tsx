@@ -3,6 +3,7 @@ import Layout from "../../components/Layout";
 import Header from "../../components/Header";
 import { SERVER_URL } from "../../constants";
 import {
+  Button,
   Calendar,
   Information,
   Normality,
@@ -19,19 +20,32 @@ export default function Temperature() {
   let [noise, setNoise] = useState(0);
   let [normality, setNormality] = useState(0);
   let [values, setValues] = useState<Data[]>([]);
+  let [probes, setProbes] = useState<string[]>([]);
+  let [probe, setProbe] = useState<string | null>(null);
 
   useEffect(() => {
     axios
-      .post(`${SERVER_URL}/temperature`, { unit: select })
+      .get(`${SERVER_URL}/temperature/probes`)
+      .then(({ data }: { data: string[] }) => {
+        setProbes(data);
+        if (data.length > 0) {
+          setProbe(data[0]);
+        }
+      });
+  }, []);
+
+  useEffect(() => {
+    axios
+      .post(`${SERVER_URL}/temperature`, { unit: select, rom: probe })
       .then((res: any) => {
         console.log(res.data);
         setValues(res.data);
       });
-  }, [select]);
+  }, [select, probe]);
 
   useEffect(() => {
     axios
-      .post(`${SERVER_URL}/temperature`, { unit: "day" })
+      .post(`${SERVER_URL}/temperature`, { unit: "day", rom: probe })
       .then(({ data }: { data: Data[] }) => {
         console.log(data);
         let { min, max, average, noise, normality } = getInfo(data);
@@ -42,14 +56,29 @@ export default function Temperature() {
         setAverage(Math.floor(average));
         setNormality(normality);
       });
-  }, [select]);
+  }, [select, probe]);
 
   return (
     <Layout>
       <div className="w-screen h-screen p-5 grid grid-rows-[1fr_25fr] grid-cols-3 gap-5">
         <Header />
         <div className="col-span-3 grid grid-rows-[1fr_5fr_5fr_2fr] gap-5">
-          <Title value="Temperatura" />
+          {probes.length > 1 ? (
+            <div className="bg-blue-950 rounded-lg text-white flex items-center justify-center gap-2 text-sm">
+              <span className="font-bold pr-2">Temperatura</span>
+              {probes.map((rom, index) => (
+                <Button
+                  key={rom}
+                  onClick={() => setProbe(rom)}
+                  status={probe == rom}
+                >
+                  {`Sonda ${index + 1}`}
+                </Button>
+              ))}
+            </div>
+          ) : (
+            <Title value="Temperatura" />
+          )}
           <Calendar
             set={setSelect}
             unit={select}
@@ -0,0 +1,264 @@
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+const RESTING_INTENSITY: f32 = 0.06;
+const RUNNING_INTENSITY: f32 = 0.45;
+const RUNNING_CADENCE: f32 = 2.4;
+const MIN_HEART_RATE: u32 = 60;
+const MAX_HEART_RATE: u32 = 220;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Kind {
+    Resting,
+    Walking,
+    Running,
+}
+
+impl Kind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Kind::Resting => "resting",
+            Kind::Walking => "walking",
+            Kind::Running => "running",
+        }
+    }
+
+    fn met(&self) -> f32 {
+        match self {
+            Kind::Resting => 1.0,
+            Kind::Walking => 3.5,
+            Kind::Running => 8.0,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Sex {
+    Male,
+    Female,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Profile {
+    pub weight: f32,
+    pub age: u32,
+    pub sex: Option<Sex>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Window {
+    pub kind: Kind,
+    pub intensity: f32,
+    pub cadence: f32,
+    pub seconds: f32,
+}
+
+pub struct ActivityClassifier {
+    rate: f32,
+    size: usize,
+    magnitudes: VecDeque<f32>,
+}
+
+impl ActivityClassifier {
+    pub fn new(rate: f32, seconds: f32) -> Self {
+        let size = (rate * seconds).max(1.0) as usize;
+
+        Self {
+            rate,
+            size,
+            magnitudes: VecDeque::with_capacity(size),
+        }
+    }
+
+    pub fn update(&mut self, magnitude: f32) -> Option<Window> {
+        self.magnitudes.push_back(magnitude);
+
+        if self.magnitudes.len() < self.size {
+            return None;
+        }
+
+        let window = classify(self.magnitudes.make_contiguous(), self.rate);
+        self.magnitudes.clear();
+        Some(window)
+    }
+}
+
+pub fn classify(magnitudes: &[f32], rate: f32) -> Window {
+    let len = magnitudes.len().max(1) as f32;
+    let mean = magnitudes.iter().sum::<f32>() / len;
+    let intensity = (magnitudes.iter().map(|m| (m - mean).powi(2)).sum::<f32>() / len).sqrt();
+
+    let crossings = magnitudes
+        .windows(2)
+        .filter(|pair| (pair[0] - mean) < 0.0 && (pair[1] - mean) >= 0.0)
+        .count();
+    let seconds = len / rate;
+    let cadence = crossings as f32 / seconds;
+
+    let kind = if intensity < RESTING_INTENSITY {
+        Kind::Resting
+    } else if intensity >= RUNNING_INTENSITY || cadence >= RUNNING_CADENCE {
+        Kind::Running
+    } else {
+        Kind::Walking
+    };
+
+    Window {
+        kind,
+        intensity,
+        cadence,
+        seconds,
+    }
+}
+
+// Keytel et al. (2005) heart-rate based estimate, averaged between the male and
+// female equations when the sex is unknown, with a MET based fallback when the
+// heart rate is not usable.
+pub fn calories(window: &Window, heart_rate: Option<u32>, profile: &Profile) -> f32 {
+    let minutes = window.seconds / 60.0;
+    let met = window.kind.met() * profile.weight * minutes / 60.0;
+
+    match heart_rate {
+        Some(hr)
+            if window.kind != Kind::Resting && (MIN_HEART_RATE..=MAX_HEART_RATE).contains(&hr) =>
+        {
+            let hr = hr as f32;
+            let age = profile.age as f32;
+            let male = -55.0969 + 0.6309 * hr + 0.1988 * profile.weight + 0.2017 * age;
+            let female = -20.4022 + 0.4472 * hr - 0.1263 * profile.weight + 0.074 * age;
+            let kilojoules = match profile.sex {
+                Some(Sex::Male) => male,
+                Some(Sex::Female) => female,
+                None => (male + female) / 2.0,
+            };
+            let per_minute = kilojoules / 4.184;
+
+            (per_minute * minutes).max(met)
+        }
+        _ => met,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f32::consts::TAU;
+
+    const RATE: f32 = 50.0;
+
+    fn profile(sex: Option<Sex>) -> Profile {
+        Profile {
+            weight: 70.0,
+            age: 30,
+            sex,
+        }
+    }
+
+    fn minute_of(kind: Kind) -> Window {
+        Window {
+            kind,
+            intensity: 0.2,
+            cadence: 1.8,
+            seconds: 60.0,
+        }
+    }
+
+    /// Seconds of acceleration swinging around 1 g at `hz`.
+    fn swinging(amplitude: f32, hz: f32, seconds: f32) -> Vec<f32> {
+        (0..(RATE * seconds) as usize)
+            .map(|i| 1.0 + amplitude * (TAU * hz * i as f32 / RATE).sin())
+            .collect()
+    }
+
+    fn close(a: f32, b: f32) -> bool {
+        (a - b).abs() < 0.01
+    }
+
+    #[test]
+    fn estimates_from_a_real_heart_rate() {
+        let window = minute_of(Kind::Walking);
+        let fallback = calories(&window, None, &profile(None));
+
+        // A walking pulse, not its distance from 85 bpm.
+        let estimate = calories(&window, Some(120), &profile(None));
+
+        assert!(close(estimate, 8.03), "{}", estimate);
+        assert!(estimate > fallback);
+        assert_eq!(calories(&window, Some(35), &profile(None)), fallback);
+    }
+
+    #[test]
+    fn uses_the_keytel_equation_of_each_sex() {
+        let window = minute_of(Kind::Walking);
+
+        let male = calories(&window, Some(120), &profile(Some(Sex::Male)));
+        let female = calories(&window, Some(120), &profile(Some(Sex::Female)));
+        let unknown = calories(&window, Some(120), &profile(None));
+
+        assert!(close(male, 9.70), "{}", male);
+        assert!(close(female, 6.37), "{}", female);
+        assert!(close(unknown, (male + female) / 2.0), "{}", unknown);
+    }
+
+    #[test]
+    fn falls_back_to_mets_without_a_usable_heart_rate() {
+        let window = minute_of(Kind::Walking);
+        let mets = 3.5 * 70.0 / 60.0;
+
+        for sex in [Some(Sex::Male), Some(Sex::Female), None] {
+            for heart_rate in [None, Some(MIN_HEART_RATE - 1), Some(MAX_HEART_RATE + 1)] {
+                let estimate = calories(&window, heart_rate, &profile(sex));
+                assert!(close(estimate, mets), "{:?} {:?}", sex, heart_rate);
+            }
+        }
+    }
+
+    #[test]
+    fn ignores_the_heart_rate_at_rest() {
+        let window = minute_of(Kind::Resting);
+
+        for sex in [Some(Sex::Male), Some(Sex::Female)] {
+            let estimate = calories(&window, Some(120), &profile(sex));
+            assert!(close(estimate, 70.0 / 60.0), "{}", estimate);
+        }
+    }
+
+    #[test]
+    fn never_estimates_below_the_mets() {
+        // A low pulse for a run gives less than the activity alone.
+        let window = minute_of(Kind::Running);
+        let estimate = calories(&window, Some(60), &profile(Some(Sex::Female)));
+
+        assert!(close(estimate, 8.0 * 70.0 / 60.0), "{}", estimate);
+    }
+
+    #[test]
+    fn classifies_by_intensity_and_cadence() {
+        assert_eq!(classify(&[1.0; 200], RATE).kind, Kind::Resting);
+        assert_eq!(classify(&swinging(0.2, 1.8, 4.0), RATE).kind, Kind::Walking);
+        assert_eq!(classify(&swinging(0.9, 1.8, 4.0), RATE).kind, Kind::Running);
+        assert_eq!(classify(&swinging(0.2, 3.0, 4.0), RATE).kind, Kind::Running);
+    }
+
+    #[test]
+    fn measures_the_cadence() {
+        let window = classify(&swinging(0.2, 2.0, 4.0), RATE);
+
+        assert!((window.cadence - 2.0).abs() <= 0.25, "{:?}", window);
+        assert!(close(window.seconds, 4.0));
+        assert!(close(window.intensity, 0.2 / 2f32.sqrt()), "{:?}", window);
+    }
+
+    #[test]
+    fn classifies_each_full_window() {
+        let mut classifier = ActivityClassifier::new(RATE, 2.0);
+        let windows = swinging(0.2, 1.8, 5.0)
+            .into_iter()
+            .filter_map(|magnitude| classifier.update(magnitude))
+            .collect::<Vec<_>>();
+
+        assert_eq!(windows.len(), 2);
+        assert!(windows.iter().all(|window| window.kind == Kind::Walking));
+    }
+}
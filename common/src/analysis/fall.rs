@@ -0,0 +1,259 @@
+use std::time::Duration;
+
+const FREE_FALL_THRESHOLD: f32 = 0.5;
+const FREE_FALL_MIN: Duration = Duration::from_millis(80);
+const IMPACT_THRESHOLD: f32 = 2.5;
+const IMPACT_WINDOW: Duration = Duration::from_millis(1000);
+const SETTLE_TIME: Duration = Duration::from_millis(500);
+const MIN_ORIENTATION_CHANGE: f32 = 45.0;
+const INACTIVITY_TOLERANCE: f32 = 0.15;
+const INACTIVITY_TIME: Duration = Duration::from_secs(2);
+const POST_IMPACT_WINDOW: Duration = Duration::from_secs(6);
+const GRAVITY_SMOOTHING: f32 = 0.1;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Vector {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+}
+
+impl Vector {
+    pub fn new(x: f32, y: f32, z: f32) -> Self {
+        Self { x, y, z }
+    }
+
+    pub fn magnitude(&self) -> f32 {
+        (self.x * self.x + self.y * self.y + self.z * self.z).sqrt()
+    }
+
+    pub fn angle(&self, other: &Vector) -> f32 {
+        let magnitudes = self.magnitude() * other.magnitude();
+
+        if magnitudes == 0.0 {
+            return 0.0;
+        }
+
+        let dot = self.x * other.x + self.y * other.y + self.z * other.z;
+        (dot / magnitudes).clamp(-1.0, 1.0).acos().to_degrees()
+    }
+
+    fn blend(&self, other: &Vector, factor: f32) -> Vector {
+        Vector {
+            x: self.x + (other.x - self.x) * factor,
+            y: self.y + (other.y - self.y) * factor,
+            z: self.z + (other.z - self.z) * factor,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Phase {
+    Idle,
+    FreeFall { since: Duration },
+    Falling { since: Duration },
+    Impact { at: Duration },
+    Resting { since: Duration, impact: Duration },
+}
+
+pub struct FallDetector {
+    phase: Phase,
+    gravity: Option<Vector>,
+    before: Option<Vector>,
+}
+
+impl FallDetector {
+    pub fn new() -> Self {
+        Self {
+            phase: Phase::Idle,
+            gravity: None,
+            before: None,
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.phase = Phase::Idle;
+        self.before = None;
+    }
+
+    pub fn update(&mut self, accel: Vector, now: Duration) -> bool {
+        let magnitude = accel.magnitude();
+
+        match self.phase {
+            Phase::Idle => {
+                if magnitude < FREE_FALL_THRESHOLD {
+                    self.before = self.gravity;
+                    self.phase = Phase::FreeFall { since: now };
+                } else if (magnitude - 1.0).abs() < INACTIVITY_TOLERANCE {
+                    self.gravity = Some(match self.gravity {
+                        Some(gravity) => gravity.blend(&accel, GRAVITY_SMOOTHING),
+                        None => accel,
+                    });
+                }
+            }
+            Phase::FreeFall { since } => {
+                if magnitude >= FREE_FALL_THRESHOLD {
+                    self.reset();
+                } else if now.saturating_sub(since) >= FREE_FALL_MIN {
+                    self.phase = Phase::Falling { since };
+                }
+            }
+            Phase::Falling { since } => {
+                if magnitude > IMPACT_THRESHOLD {
+                    self.phase = Phase::Impact { at: now };
+                } else if now.saturating_sub(since) > IMPACT_WINDOW {
+                    self.reset();
+                }
+            }
+            Phase::Impact { at } => {
+                if now.saturating_sub(at) >= SETTLE_TIME {
+                    self.phase = Phase::Resting {
+                        since: now,
+                        impact: at,
+                    };
+                }
+            }
+            Phase::Resting { since, impact } => {
+                if (magnitude - 1.0).abs() > INACTIVITY_TOLERANCE {
+                    if now.saturating_sub(impact) > POST_IMPACT_WINDOW {
+                        self.reset();
+                    } else {
+                        self.phase = Phase::Resting { since: now, impact };
+                    }
+                } else if now.saturating_sub(since) >= INACTIVITY_TIME {
+                    let turned = self
+                        .before
+                        .is_some_and(|before| before.angle(&accel) >= MIN_ORIENTATION_CHANGE);
+
+                    self.reset();
+                    self.gravity = Some(accel);
+                    return turned;
+                }
+            }
+        }
+
+        false
+    }
+}
+
+impl Default for FallDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STEP: Duration = Duration::from_millis(10);
+
+    const UPRIGHT: Vector = Vector {
+        x: 0.0,
+        y: 0.0,
+        z: 1.0,
+    };
+    const LYING: Vector = Vector {
+        x: 1.0,
+        y: 0.0,
+        z: 0.0,
+    };
+    const WEIGHTLESS: Vector = Vector {
+        x: 0.0,
+        y: 0.0,
+        z: 0.1,
+    };
+    const HIT: Vector = Vector {
+        x: 4.0,
+        y: 0.0,
+        z: 0.0,
+    };
+
+    /// Feeds each reading for its duration at the sensor's rate, returning
+    /// when the detector fired, if it did.
+    fn run(detector: &mut FallDetector, timeline: &[(Vector, u64)]) -> Option<Duration> {
+        let mut now = Duration::ZERO;
+        let mut fired = None;
+
+        for (accel, ms) in timeline {
+            let until = now + Duration::from_millis(*ms);
+
+            while now < until {
+                if detector.update(*accel, now) && fired.is_none() {
+                    fired = Some(now);
+                }
+
+                now += STEP;
+            }
+        }
+
+        fired
+    }
+
+    #[test]
+    fn detects_a_fall() {
+        let timeline = [(UPRIGHT, 1000), (WEIGHTLESS, 300), (HIT, 20), (LYING, 4000)];
+        let fired = run(&mut FallDetector::new(), &timeline).expect("no fall detected");
+
+        // Settled after the impact, then still for long enough.
+        let impact = Duration::from_millis(1300);
+        assert!(
+            fired >= impact + SETTLE_TIME + INACTIVITY_TIME,
+            "{:?}",
+            fired
+        );
+    }
+
+    #[test]
+    fn ignores_an_impact_that_leaves_the_wearer_upright() {
+        let timeline = [
+            (UPRIGHT, 1000),
+            (WEIGHTLESS, 300),
+            (HIT, 20),
+            (UPRIGHT, 4000),
+        ];
+
+        assert_eq!(run(&mut FallDetector::new(), &timeline), None);
+    }
+
+    #[test]
+    fn forgets_a_free_fall_without_an_impact_in_time() {
+        let timeline = [
+            (UPRIGHT, 1000),
+            (WEIGHTLESS, 300),
+            (UPRIGHT, 1000),
+            (HIT, 20),
+            (LYING, 4000),
+        ];
+
+        assert_eq!(run(&mut FallDetector::new(), &timeline), None);
+    }
+
+    #[test]
+    fn ignores_a_brief_dip() {
+        let timeline = [(UPRIGHT, 1000), (WEIGHTLESS, 50), (HIT, 20), (LYING, 4000)];
+
+        assert_eq!(run(&mut FallDetector::new(), &timeline), None);
+    }
+
+    #[test]
+    fn needs_a_baseline_orientation() {
+        // Falling before the detector ever saw the wearer at rest.
+        let timeline = [(WEIGHTLESS, 300), (HIT, 20), (LYING, 4000)];
+
+        assert_eq!(run(&mut FallDetector::new(), &timeline), None);
+    }
+
+    #[test]
+    fn gives_up_when_the_wearer_keeps_moving() {
+        let mut timeline = vec![(UPRIGHT, 1000), (WEIGHTLESS, 300), (HIT, 20)];
+
+        // Never still for long enough within the post-impact window.
+        for _ in 0..8 {
+            timeline.extend([(LYING, 1000), (HIT, 20)]);
+        }
+
+        timeline.push((LYING, 4000));
+        assert_eq!(run(&mut FallDetector::new(), &timeline), None);
+    }
+}
@@ -0,0 +1,215 @@
+use std::collections::VecDeque;
+
+/// The shortest and longest beat intervals taken as real, in milliseconds.
+pub const MIN_INTERVAL_MS: u32 = 300;
+pub const MAX_INTERVAL_MS: u32 = 2000;
+const MIN_INTERVAL: f32 = MIN_INTERVAL_MS as f32;
+const MAX_INTERVAL: f32 = MAX_INTERVAL_MS as f32;
+const MAX_DEVIATION: f32 = 0.2;
+const MAX_REJECTED: u32 = 5;
+const NN50: f32 = 50.0;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Metrics {
+    pub rmssd: f32,
+    pub sdnn: f32,
+    pub pnn50: f32,
+    pub intervals: usize,
+}
+
+pub struct Intervals {
+    size: usize,
+    values: VecDeque<f32>,
+    rejected: u32,
+}
+
+impl Intervals {
+    pub fn new(size: usize) -> Self {
+        Self {
+            size,
+            values: VecDeque::with_capacity(size),
+            rejected: 0,
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.values.clear();
+        self.rejected = 0;
+    }
+
+    pub fn push(&mut self, interval: f32) -> bool {
+        if !(MIN_INTERVAL..=MAX_INTERVAL).contains(&interval) {
+            return false;
+        }
+
+        if let Some(reference) = self.reference() {
+            if (interval - reference).abs() > reference * MAX_DEVIATION {
+                self.rejected += 1;
+
+                if self.rejected < MAX_REJECTED {
+                    return false;
+                }
+
+                self.values.clear();
+            }
+        }
+
+        self.rejected = 0;
+
+        if self.values.len() == self.size {
+            self.values.pop_front();
+        }
+
+        self.values.push_back(interval);
+        true
+    }
+
+    fn reference(&self) -> Option<f32> {
+        let mut recent = self
+            .values
+            .iter()
+            .rev()
+            .take(5)
+            .copied()
+            .collect::<Vec<_>>();
+
+        if recent.is_empty() {
+            return None;
+        }
+
+        recent.sort_by(|a, b| a.total_cmp(b));
+        Some(recent[recent.len() / 2])
+    }
+
+    pub fn metrics(&self, min_intervals: usize) -> Option<Metrics> {
+        if self.values.len() < min_intervals {
+            return None;
+        }
+
+        metrics(self.values.iter().copied().collect::<Vec<_>>().as_slice())
+    }
+}
+
+/// Time domain metrics of a series of beat intervals, which takes at least
+/// two of them.
+pub fn metrics(intervals: &[f32]) -> Option<Metrics> {
+    if intervals.len() < 2 {
+        return None;
+    }
+
+    let len = intervals.len() as f32;
+    let mean = intervals.iter().sum::<f32>() / len;
+    let sdnn = (intervals.iter().map(|i| (i - mean).powi(2)).sum::<f32>() / (len - 1.0)).sqrt();
+
+    let differences = intervals
+        .windows(2)
+        .map(|pair| pair[1] - pair[0])
+        .collect::<Vec<_>>();
+    let count = differences.len() as f32;
+    let rmssd = (differences.iter().map(|d| d * d).sum::<f32>() / count).sqrt();
+    let pnn50 = differences.iter().filter(|d| d.abs() > NN50).count() as f32 / count * 100.0;
+
+    Some(Metrics {
+        rmssd,
+        sdnn,
+        pnn50,
+        intervals: intervals.len(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: f32, b: f32) -> bool {
+        (a - b).abs() < 0.01
+    }
+
+    fn window(values: &[f32]) -> Intervals {
+        let mut intervals = Intervals::new(60);
+
+        for value in values {
+            intervals.push(*value);
+        }
+
+        intervals
+    }
+
+    #[test]
+    fn computes_metrics_of_a_known_series() {
+        let metrics = metrics(&[800.0, 850.0, 800.0, 750.0, 800.0]).unwrap();
+
+        assert!(close(metrics.rmssd, 50.0), "{:?}", metrics);
+        assert!(close(metrics.sdnn, 35.36), "{:?}", metrics);
+        // Differences of exactly 50 ms do not count.
+        assert_eq!(metrics.pnn50, 0.0);
+        assert_eq!(metrics.intervals, 5);
+
+        let metrics = super::metrics(&[800.0, 860.0, 800.0, 840.0]).unwrap();
+        assert!(close(metrics.pnn50, 66.67), "{:?}", metrics);
+    }
+
+    #[test]
+    fn needs_two_intervals() {
+        assert_eq!(metrics(&[]), None);
+        assert_eq!(metrics(&[800.0]), None);
+        assert!(metrics(&[800.0, 810.0]).is_some());
+
+        assert_eq!(window(&[800.0]).metrics(0), None);
+        assert_eq!(window(&[800.0, 810.0, 790.0]).metrics(4), None);
+        assert!(window(&[800.0, 810.0, 790.0]).metrics(3).is_some());
+    }
+
+    #[test]
+    fn rejects_intervals_no_heart_beats_at() {
+        let mut intervals = Intervals::new(60);
+
+        assert!(!intervals.push(MIN_INTERVAL - 1.0));
+        assert!(!intervals.push(MAX_INTERVAL + 1.0));
+        assert!(intervals.push(MIN_INTERVAL));
+        assert!(intervals.push(MAX_INTERVAL.min(MIN_INTERVAL * 1.2)));
+    }
+
+    #[test]
+    fn rejects_an_artifact_among_normal_beats() {
+        let mut intervals = window(&[800.0, 810.0, 790.0, 805.0]);
+
+        // A missed beat reads as twice the interval, an extra one as half.
+        assert!(!intervals.push(1600.0));
+        assert!(!intervals.push(400.0));
+        assert!(intervals.push(795.0));
+
+        let metrics = intervals.metrics(2).unwrap();
+        assert_eq!(metrics.intervals, 5);
+        assert!(metrics.rmssd < 20.0, "{:?}", metrics);
+    }
+
+    #[test]
+    fn follows_a_lasting_change_of_rhythm() {
+        let mut intervals = window(&[800.0, 810.0, 790.0, 805.0]);
+
+        // The first few are taken for artifacts, then the new rate for real.
+        let accepted = (0..MAX_REJECTED)
+            .map(|_| intervals.push(500.0))
+            .collect::<Vec<_>>();
+
+        assert_eq!(accepted.iter().filter(|accepted| **accepted).count(), 1);
+        assert_eq!(accepted.last(), Some(&true));
+        assert!(intervals.push(505.0));
+        assert_eq!(
+            intervals.metrics(2).map(|metrics| metrics.intervals),
+            Some(2)
+        );
+    }
+
+    #[test]
+    fn keeps_the_latest_intervals() {
+        let mut intervals = Intervals::new(3);
+
+        for value in [800.0, 810.0, 820.0, 830.0] {
+            intervals.push(value);
+        }
+
+        assert_eq!(intervals.metrics(0).unwrap().intervals, 3);
+    }
+}
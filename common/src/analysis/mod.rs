@@ -0,0 +1,3 @@
+pub mod fall;
+pub mod activity;
+pub mod hrv;
@@ -0,0 +1,563 @@
+use super::{Alert, Screen, View};
+use crate::{
+    images::{BATTERY, BATTERY_UNKNOWN, CLOCK, ENVELOPE, HOURGLASS, MQTT, WIFI, WIFI_OFF},
+    utils::{
+        clock::Source,
+        connection,
+        history::{Series, MINUTES},
+    },
+};
+use embedded_graphics::{
+    image::{Image, ImageRaw},
+    mono_font::{
+        ascii::{FONT_10X20, FONT_4X6, FONT_6X10, FONT_6X13},
+        MonoTextStyle,
+    },
+    pixelcolor::BinaryColor,
+    prelude::*,
+    primitives::{Circle, Line, PrimitiveStyle},
+    text::{Alignment, Baseline, Text, TextStyleBuilder},
+};
+
+const CENTER: i32 = 64;
+const VALUE_Y: i32 = 20;
+const UNIT_Y: i32 = 42;
+const LINE_Y: i32 = 14;
+const LINE_HEIGHT: i32 = 9;
+const BATTERY_X: i32 = 110;
+const WIFI_X: i32 = 96;
+const MQTT_X: i32 = 87;
+const TIME_X: i32 = 78;
+const QUEUE_X: i32 = 68;
+// The pending count is drawn under the envelope, up to what fits.
+const QUEUE_Y: i32 = 6;
+const QUEUE_SHOWN: usize = 99;
+const GRAPH_X: i32 = 4;
+const GRAPH_TOP: i32 = 15;
+const GRAPH_HEIGHT: i32 = 32;
+// Pixels per minute, so the hour spans 120 columns.
+const GRAPH_STEP: i32 = 2;
+const LABEL_Y: i32 = 51;
+const DOTS_Y: i32 = 61;
+const DOT_SPACING: i32 = 8;
+const DOT_SIZE: u32 = 3;
+
+fn small() -> MonoTextStyle<'static, BinaryColor> {
+    MonoTextStyle::new(&FONT_6X13, BinaryColor::On)
+}
+
+fn big() -> MonoTextStyle<'static, BinaryColor> {
+    MonoTextStyle::new(&FONT_10X20, BinaryColor::On)
+}
+
+fn centered<D>(
+    target: &mut D,
+    text: &str,
+    y: i32,
+    style: MonoTextStyle<'static, BinaryColor>,
+) -> Result<(), D::Error>
+where
+    D: DrawTarget<Color = BinaryColor>,
+{
+    let layout = TextStyleBuilder::new()
+        .alignment(Alignment::Center)
+        .baseline(Baseline::Top)
+        .build();
+
+    Text::with_text_style(text, Point::new(CENTER, y), style, layout).draw(target)?;
+    Ok(())
+}
+
+fn header<D>(target: &mut D, title: &str, view: &View) -> Result<(), D::Error>
+where
+    D: DrawTarget<Color = BinaryColor>,
+{
+    Text::with_baseline(title, Point::zero(), small(), Baseline::Top).draw(target)?;
+    icons(target, view)
+}
+
+fn alert<D>(target: &mut D, alert: &Alert, view: &View) -> Result<(), D::Error>
+where
+    D: DrawTarget<Color = BinaryColor>,
+{
+    let title = match alert {
+        Alert::Countdown { reason, .. } => match reason.as_str() {
+            "sos" => "SOS",
+            "fall" => "Fall",
+            reason => reason,
+        },
+        Alert::Sending { .. } | Alert::Delivered => "SOS",
+    };
+
+    header(target, title, view)?;
+
+    match alert {
+        Alert::Countdown { remaining, .. } => {
+            let seconds = (remaining.as_millis() as u64).div_ceil(1000);
+
+            centered(target, &seconds.to_string(), VALUE_Y, big())?;
+            centered(target, "press to cancel", UNIT_Y, small())
+        }
+        Alert::Sending { attempt } => {
+            let detail = match attempt {
+                0 | 1 => "sending...".to_string(),
+                attempt => format!("sending, try {}", attempt),
+            };
+
+            centered(target, "SOS", VALUE_Y, big())?;
+            centered(target, &detail, UNIT_Y, small())
+        }
+        Alert::Delivered => {
+            centered(target, "SOS", VALUE_Y, big())?;
+            centered(target, "help notified", UNIT_Y, small())
+        }
+    }
+}
+
+fn icon<D>(target: &mut D, data: &[u8], width: u32, x: i32) -> Result<(), D::Error>
+where
+    D: DrawTarget<Color = BinaryColor>,
+{
+    let raw: ImageRaw<BinaryColor> = ImageRaw::new(data, width);
+    Image::new(&raw, Point::new(x, 0)).draw(target)?;
+    Ok(())
+}
+
+fn icons<D>(target: &mut D, view: &View) -> Result<(), D::Error>
+where
+    D: DrawTarget<Color = BinaryColor>,
+{
+    let status = &view.status;
+
+    match status.charge() {
+        Some(charge) => icon(target, &BATTERY[charge], 18, BATTERY_X)?,
+        None => icon(target, &BATTERY_UNKNOWN, 18, BATTERY_X)?,
+    }
+
+    match status.signal() {
+        Some(signal) => icon(target, &WIFI[signal], 13, WIFI_X)?,
+        None => icon(target, &WIFI_OFF, 13, WIFI_X)?,
+    }
+
+    let mqtt = match status.mqtt {
+        connection::Status::Disconnected => &MQTT[0],
+        connection::Status::Connecting => &MQTT[1],
+        connection::Status::Connected => &MQTT[2],
+    };
+    icon(target, mqtt, 7, MQTT_X)?;
+
+    match status.time {
+        Source::None => icon(target, &HOURGLASS, 7, TIME_X)?,
+        Source::Server | Source::Sntp => icon(target, &CLOCK, 7, TIME_X)?,
+    }
+
+    if status.queue > 0 {
+        let count = status.queue.min(QUEUE_SHOWN).to_string();
+        let style = MonoTextStyle::new(&FONT_4X6, BinaryColor::On);
+
+        icon(target, &ENVELOPE, 7, QUEUE_X)?;
+        Text::with_baseline(&count, Point::new(QUEUE_X, QUEUE_Y), style, Baseline::Top)
+            .draw(target)?;
+    }
+
+    Ok(())
+}
+
+fn reading<D>(target: &mut D, value: Option<String>, unit: &str) -> Result<(), D::Error>
+where
+    D: DrawTarget<Color = BinaryColor>,
+{
+    let value = value.unwrap_or_else(|| "--".to_string());
+
+    centered(target, &value, VALUE_Y, big())?;
+    centered(target, unit, UNIT_Y, small())
+}
+
+fn clock<D>(target: &mut D, view: &View) -> Result<(), D::Error>
+where
+    D: DrawTarget<Color = BinaryColor>,
+{
+    match view.now {
+        Some(now) => centered(target, &now.format("%H:%M:%S").to_string(), VALUE_Y, big()),
+        None => reading(target, None, "waiting for time"),
+    }
+}
+
+fn status<D>(target: &mut D, view: &View) -> Result<(), D::Error>
+where
+    D: DrawTarget<Color = BinaryColor>,
+{
+    let status = &view.status;
+    let mqtt = match status.mqtt {
+        connection::Status::Disconnected => "offline",
+        connection::Status::Connecting => "connecting",
+        connection::Status::Connected => "online",
+    };
+    let time = match status.time {
+        Source::None => "unsynced",
+        Source::Server => "server",
+        Source::Sntp => "sntp",
+    };
+    let wifi = match (status.wifi, status.rssi) {
+        (true, Some(rssi)) => format!("{} dBm", rssi),
+        (true, None) => "up".to_string(),
+        (false, _) => "down".to_string(),
+    };
+    let battery = match status.battery {
+        Some(battery) => format!("{}%", battery),
+        None => "unknown".to_string(),
+    };
+    let lines = [
+        format!("WiFi     {}", wifi),
+        format!("MQTT     {}", mqtt),
+        format!("Time     {}", time),
+        format!("Queue    {}", status.queue),
+        format!("Battery  {}", battery),
+    ];
+    let style = MonoTextStyle::new(&FONT_6X10, BinaryColor::On);
+
+    for (i, line) in lines.iter().enumerate() {
+        let position = Point::new(0, LINE_Y + i as i32 * LINE_HEIGHT);
+        Text::with_baseline(line, position, style, Baseline::Top).draw(target)?;
+    }
+
+    Ok(())
+}
+
+fn small_label<D>(target: &mut D, text: &str, x: i32, alignment: Alignment) -> Result<(), D::Error>
+where
+    D: DrawTarget<Color = BinaryColor>,
+{
+    let style = MonoTextStyle::new(&FONT_4X6, BinaryColor::On);
+    let layout = TextStyleBuilder::new()
+        .alignment(alignment)
+        .baseline(Baseline::Top)
+        .build();
+
+    Text::with_text_style(text, Point::new(x, LABEL_Y), style, layout).draw(target)?;
+    Ok(())
+}
+
+// Where a point lands in the graph, the range stretched over its height. A
+// flat series is drawn through the middle.
+fn plot(i: usize, value: f32, (min, max): (f32, f32)) -> Point {
+    let bottom = GRAPH_TOP + GRAPH_HEIGHT - 1;
+    let offset = if max > min {
+        ((value - min) / (max - min) * (GRAPH_HEIGHT - 1) as f32).round() as i32
+    } else {
+        GRAPH_HEIGHT / 2
+    };
+
+    Point::new(GRAPH_X + i as i32 * GRAPH_STEP, bottom - offset)
+}
+
+/// A sparkline of the last hour with its lowest and highest points.
+fn trend<D>(target: &mut D, series: &Series, precision: usize) -> Result<(), D::Error>
+where
+    D: DrawTarget<Color = BinaryColor>,
+{
+    let Some(range) = series.range() else {
+        return centered(target, "no data yet", VALUE_Y, small());
+    };
+
+    let points = series.points();
+    let style = PrimitiveStyle::with_stroke(BinaryColor::On, 1);
+
+    for (i, point) in points.iter().enumerate() {
+        let Some(value) = *point else {
+            continue;
+        };
+        let start = plot(i, value, range);
+
+        match points.get(i + 1).copied().flatten() {
+            Some(next) => Line::new(start, plot(i + 1, next, range))
+                .into_styled(style)
+                .draw(target)?,
+            None => Pixel(start, BinaryColor::On).draw(target)?,
+        }
+    }
+
+    let (min, max) = range;
+    small_label(
+        target,
+        &format!("min {:.*}", precision, min),
+        GRAPH_X,
+        Alignment::Left,
+    )?;
+    small_label(
+        target,
+        &format!("max {:.*}", precision, max),
+        GRAPH_X + (MINUTES as i32 - 1) * GRAPH_STEP,
+        Alignment::Right,
+    )
+}
+
+// One dot per screen, the current one filled.
+fn dots<D>(target: &mut D, index: usize) -> Result<(), D::Error>
+where
+    D: DrawTarget<Color = BinaryColor>,
+{
+    let count = Screen::ALL.len() as i32;
+    let left = CENTER - (count - 1) * DOT_SPACING / 2 - DOT_SIZE as i32 / 2;
+
+    for i in 0..count {
+        let style = if i as usize == index {
+            PrimitiveStyle::with_fill(BinaryColor::On)
+        } else {
+            PrimitiveStyle::with_stroke(BinaryColor::On, 1)
+        };
+
+        Circle::new(Point::new(left + i * DOT_SPACING, DOTS_Y), DOT_SIZE)
+            .into_styled(style)
+            .draw(target)?;
+    }
+
+    Ok(())
+}
+
+/// Renders a whole frame of `screen`, the `index`-th page, unless an alert
+/// takes its place.
+pub fn draw<D>(target: &mut D, screen: Screen, index: usize, view: &View) -> Result<(), D::Error>
+where
+    D: DrawTarget<Color = BinaryColor>,
+{
+    target.clear(BinaryColor::Off)?;
+
+    if let Some(current) = &view.alert {
+        return alert(target, current, view);
+    }
+
+    let title = match (screen, view.now) {
+        (Screen::Clock, Some(now)) => now.format("%d/%m/%Y").to_string(),
+        _ => screen.title().to_string(),
+    };
+
+    header(target, &title, view)?;
+
+    let vitals = &view.vitals;
+    let history = &view.history;
+
+    match screen {
+        Screen::Clock => clock(target, view)?,
+        Screen::HeartRate => reading(target, vitals.heart_rate.map(|bpm| bpm.to_string()), "bpm")?,
+        Screen::Temperature => reading(
+            target,
+            vitals.temperature.map(|celsius| format!("{:.1}", celsius)),
+            "C",
+        )?,
+        Screen::HeartRateTrend => trend(target, &history.heart_rate, 0)?,
+        Screen::TemperatureTrend => trend(target, &history.temperature, 1)?,
+        Screen::StepsTrend => trend(target, &history.steps, 0)?,
+        Screen::Steps => {
+            let unit = match vitals.activity {
+                Some(activity) => format!("steps - {}", activity),
+                None => "steps".to_string(),
+            };
+
+            reading(target, Some(vitals.steps.to_string()), &unit)?
+        }
+        Screen::Status => status(target, view)?,
+    }
+
+    dots(target, index)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::{history::History, status::Status, vitals::Vitals};
+    use chrono::NaiveDate;
+    use std::time::Duration;
+
+    const WIDTH: usize = 128;
+    const HEIGHT: usize = 64;
+
+    /// The panel's frame buffer, refusing pixels that fall outside it.
+    struct Frame([[bool; WIDTH]; HEIGHT]);
+
+    impl Frame {
+        fn new() -> Self {
+            Self([[false; WIDTH]; HEIGHT])
+        }
+
+        fn lit(&self, x: i32, y: i32) -> bool {
+            self.0[y as usize][x as usize]
+        }
+    }
+
+    impl OriginDimensions for Frame {
+        fn size(&self) -> Size {
+            Size::new(WIDTH as u32, HEIGHT as u32)
+        }
+    }
+
+    impl DrawTarget for Frame {
+        type Color = BinaryColor;
+        type Error = Point;
+
+        fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Point>
+        where
+            I: IntoIterator<Item = Pixel<BinaryColor>>,
+        {
+            for Pixel(point, color) in pixels {
+                let (x, y) = (point.x as usize, point.y as usize);
+
+                if point.x < 0 || point.y < 0 || x >= WIDTH || y >= HEIGHT {
+                    return Err(point);
+                }
+
+                self.0[y][x] = color.is_on();
+            }
+
+            Ok(())
+        }
+    }
+
+    fn render(screen: Screen, index: usize, view: &View) -> Frame {
+        let mut frame = Frame::new();
+        draw(&mut frame, screen, index, view)
+            .unwrap_or_else(|point| panic!("{:?} drew outside the display at {:?}", screen, point));
+        frame
+    }
+
+    fn empty() -> View {
+        View {
+            now: None,
+            vitals: Vitals::default(),
+            history: Box::default(),
+            status: Status::new(),
+            alert: None,
+        }
+    }
+
+    /// Every field set, with values as wide as they get.
+    fn full() -> View {
+        let mut history = History::new();
+
+        for minute in 0..MINUTES as u64 * 2 {
+            let vitals = Vitals {
+                heart_rate: Some(40 + (minute as u32 * 7) % 180),
+                temperature: Some(30.0 + (minute % 13) as f32 * 0.7),
+                steps: minute as u32 * 120,
+                activity: None,
+            };
+            history.record(&vitals, minute * 60 * 1000);
+        }
+
+        View {
+            now: NaiveDate::from_ymd_opt(2024, 12, 31)
+                .and_then(|date| date.and_hms_opt(23, 59, 59)),
+            vitals: Vitals {
+                heart_rate: Some(220),
+                temperature: Some(-12.5),
+                steps: 123_456,
+                activity: Some("running"),
+            },
+            history: Box::new(history),
+            status: Status {
+                wifi: true,
+                rssi: Some(-40),
+                mqtt: connection::Status::Connected,
+                time: Source::Sntp,
+                queue: 12_345,
+                battery: Some(100),
+            },
+            alert: None,
+        }
+    }
+
+    /// A history whose every point is the same, so its range is empty.
+    fn flat() -> View {
+        let mut history = History::new();
+
+        for minute in 0..10 {
+            let vitals = Vitals {
+                heart_rate: Some(70),
+                temperature: Some(36.6),
+                steps: 0,
+                activity: None,
+            };
+            history.record(&vitals, minute * 60 * 1000);
+        }
+
+        View {
+            history: Box::new(history),
+            ..empty()
+        }
+    }
+
+    fn countdown(remaining: Duration) -> View {
+        View {
+            alert: Some(Alert::Countdown {
+                reason: "fall".to_string(),
+                remaining,
+            }),
+            ..empty()
+        }
+    }
+
+    fn dot(index: usize) -> (i32, i32) {
+        let count = Screen::ALL.len() as i32;
+        let left = CENTER - (count - 1) * DOT_SPACING / 2 - DOT_SIZE as i32 / 2;
+        let radius = DOT_SIZE as i32 / 2;
+
+        (left + index as i32 * DOT_SPACING + radius, DOTS_Y + radius)
+    }
+
+    #[test]
+    fn draws_every_screen_inside_the_display() {
+        for view in [empty(), full(), flat()] {
+            for (index, screen) in Screen::ALL.into_iter().enumerate() {
+                render(screen, index, &view);
+            }
+        }
+    }
+
+    #[test]
+    fn draws_every_alert_inside_the_display() {
+        let alerts = [
+            Alert::Countdown {
+                reason: "sos".to_string(),
+                remaining: Duration::from_secs(999),
+            },
+            Alert::Sending { attempt: 1 },
+            Alert::Sending { attempt: 1000 },
+            Alert::Delivered,
+        ];
+
+        for alert in alerts {
+            let view = View {
+                alert: Some(alert),
+                ..full()
+            };
+            render(Screen::Clock, 0, &view);
+        }
+    }
+
+    #[test]
+    fn fills_the_dot_of_the_current_page() {
+        for (index, screen) in Screen::ALL.into_iter().enumerate() {
+            let frame = render(screen, index, &empty());
+
+            for other in 0..Screen::ALL.len() {
+                let (x, y) = dot(other);
+                assert_eq!(frame.lit(x, y), other == index, "{:?}", screen);
+            }
+        }
+    }
+
+    #[test]
+    fn an_alert_takes_the_place_of_the_page() {
+        let frame = render(Screen::HeartRate, 1, &countdown(Duration::from_secs(5)));
+
+        assert!((DOTS_Y..HEIGHT as i32).all(|y| (0..WIDTH as i32).all(|x| !frame.lit(x, y))));
+    }
+
+    #[test]
+    fn counts_down_in_whole_seconds_rounded_up() {
+        let shown = |ms| render(Screen::Clock, 0, &countdown(Duration::from_millis(ms))).0;
+
+        assert_eq!(shown(9_200), shown(10_000));
+        assert_ne!(shown(9_000), shown(10_000));
+    }
+}
@@ -0,0 +1,135 @@
+use crate::utils::{history::History, status::Status, vitals::Vitals};
+use chrono::NaiveDateTime;
+use std::{
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Duration,
+};
+
+pub mod layout;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Screen {
+    Clock,
+    HeartRate,
+    HeartRateTrend,
+    Temperature,
+    TemperatureTrend,
+    Steps,
+    StepsTrend,
+    Status,
+}
+
+impl Screen {
+    pub const ALL: [Screen; 8] = [
+        Screen::Clock,
+        Screen::HeartRate,
+        Screen::HeartRateTrend,
+        Screen::Temperature,
+        Screen::TemperatureTrend,
+        Screen::Steps,
+        Screen::StepsTrend,
+        Screen::Status,
+    ];
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            Screen::Clock => "Clock",
+            Screen::HeartRate => "Heart rate",
+            Screen::HeartRateTrend => "HR 1h",
+            Screen::Temperature => "Temp",
+            Screen::TemperatureTrend => "Temp 1h",
+            Screen::Steps => "Steps",
+            Screen::StepsTrend => "Steps 1h",
+            Screen::Status => "Status",
+        }
+    }
+}
+
+/// The page shown on the display, moved forward by the button.
+pub struct Screens {
+    current: AtomicUsize,
+}
+
+impl Screens {
+    pub fn new() -> Self {
+        Self {
+            current: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn index(&self) -> usize {
+        self.current.load(Ordering::Relaxed) % Screen::ALL.len()
+    }
+
+    pub fn current(&self) -> Screen {
+        Screen::ALL[self.index()]
+    }
+
+    pub fn next(&self) -> Screen {
+        let _ = self
+            .current
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |current| {
+                Some((current + 1) % Screen::ALL.len())
+            });
+        self.current()
+    }
+
+    pub fn home(&self) {
+        self.current.store(0, Ordering::Relaxed);
+    }
+}
+
+impl Default for Screens {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Takes over the display while something needs the wearer's attention.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Alert {
+    /// An alarm that a press still cancels.
+    Countdown {
+        reason: String,
+        remaining: Duration,
+    },
+    /// An SOS report on its way, with the number of the current try.
+    Sending {
+        attempt: u32,
+    },
+    Delivered,
+}
+
+/// Everything a screen needs, taken at once so a frame is consistent.
+#[derive(Clone, Debug)]
+pub struct View {
+    /// Local time, once the clock has been synced.
+    pub now: Option<NaiveDateTime>,
+    pub vitals: Vitals,
+    // Boxed to keep the hour of points off the display task's stack.
+    pub history: Box<History>,
+    pub status: Status,
+    pub alert: Option<Alert>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pages_wrap_around_and_return_home() {
+        let screens = Screens::new();
+        assert_eq!(screens.current(), Screen::Clock);
+
+        for screen in Screen::ALL.iter().skip(1) {
+            assert_eq!(screens.next(), *screen);
+        }
+
+        assert_eq!(screens.next(), Screen::Clock);
+
+        screens.next();
+        screens.next();
+        screens.home();
+        assert_eq!(screens.index(), 0);
+    }
+}
@@ -0,0 +1,287 @@
+use crate::drivers::onewire::{crc8, OneWire, Rom, ALARM_SEARCH, SEARCH_ROM};
+use anyhow::{anyhow, Result};
+use embedded_hal::{
+    blocking::delay::{DelayMs, DelayUs},
+    digital::v2::{InputPin, OutputPin},
+};
+use log::info;
+use std::{fmt::Debug, thread, time::Duration};
+
+pub use crate::drivers::onewire::rom_id;
+
+const FAMILY_CODE: u8 = 0x28;
+const CONVERT_T: u8 = 0x44;
+const WRITE_SCRATCHPAD: u8 = 0x4E;
+const READ_SCRATCHPAD: u8 = 0xBE;
+const COPY_SCRATCHPAD: u8 = 0x48;
+const EEPROM_WRITE_TIME: u16 = 10;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Resolution {
+    Bits9,
+    Bits10,
+    Bits11,
+    Bits12,
+}
+
+impl Resolution {
+    fn config(&self) -> u8 {
+        match self {
+            Resolution::Bits9 => 0x1F,
+            Resolution::Bits10 => 0x3F,
+            Resolution::Bits11 => 0x5F,
+            Resolution::Bits12 => 0x7F,
+        }
+    }
+
+    pub fn conversion_time(&self) -> u16 {
+        match self {
+            Resolution::Bits9 => 94,
+            Resolution::Bits10 => 188,
+            Resolution::Bits11 => 375,
+            Resolution::Bits12 => 750,
+        }
+    }
+
+    fn mask(&self) -> i16 {
+        match self {
+            Resolution::Bits9 => !0x07,
+            Resolution::Bits10 => !0x03,
+            Resolution::Bits11 => !0x01,
+            Resolution::Bits12 => !0x00,
+        }
+    }
+}
+
+pub struct Scratchpad {
+    pub raw: i16,
+    pub high: i8,
+    pub low: i8,
+    pub config: u8,
+}
+
+pub struct Ds18b20<P, D> {
+    bus: OneWire<P, D>,
+    roms: Vec<Rom>,
+    resolution: Resolution,
+}
+
+impl<P, D, E> Ds18b20<P, D>
+where
+    P: InputPin<Error = E> + OutputPin<Error = E>,
+    D: DelayUs<u16> + DelayMs<u16>,
+    E: Debug,
+{
+    pub fn new(bus: OneWire<P, D>) -> Result<Self> {
+        let mut ds18b20 = Self {
+            bus,
+            roms: Vec::new(),
+            resolution: Resolution::Bits12,
+        };
+
+        ds18b20.begin()?;
+        Ok(ds18b20)
+    }
+
+    pub fn begin(&mut self) -> Result<()> {
+        self.roms = self
+            .bus
+            .search(SEARCH_ROM)?
+            .into_iter()
+            .filter(|rom| rom[0] == FAMILY_CODE)
+            .collect();
+        info!("Found {} ds18b20 probes", self.roms.len());
+
+        if self.roms.is_empty() {
+            return Err(anyhow!("No ds18b20 probes found"));
+        }
+
+        for rom in self.roms.clone() {
+            let scratchpad = self.read_scratchpad(&rom)?;
+            let config = self.resolution.config();
+            self.write_scratchpad(&rom, scratchpad.high, scratchpad.low, config)?;
+        }
+
+        Ok(())
+    }
+
+    fn read_scratchpad(&mut self, rom: &Rom) -> Result<Scratchpad> {
+        let mut data = [0u8; 9];
+
+        self.bus.select(Some(rom))?;
+        self.bus.write_byte(READ_SCRATCHPAD)?;
+        self.bus.read_bytes(&mut data)?;
+
+        // A probe held low reads as zeros, which pass the crc.
+        if data.iter().all(|byte| *byte == 0) {
+            return Err(anyhow!("Empty scratchpad for {}", rom_id(rom)));
+        }
+
+        if crc8(&data[..8]) != data[8] {
+            return Err(anyhow!("Invalid scratchpad crc for {}", rom_id(rom)));
+        }
+
+        Ok(Scratchpad {
+            raw: i16::from_le_bytes([data[0], data[1]]),
+            high: data[2] as i8,
+            low: data[3] as i8,
+            config: data[4],
+        })
+    }
+
+    fn write_scratchpad(&mut self, rom: &Rom, high: i8, low: i8, config: u8) -> Result<()> {
+        self.bus.select(Some(rom))?;
+        self.bus
+            .write_bytes(&[WRITE_SCRATCHPAD, high as u8, low as u8, config])
+    }
+
+    pub fn roms(&self) -> &[Rom] {
+        &self.roms
+    }
+
+    pub fn convert(&mut self) -> Result<()> {
+        self.bus.select(None)?;
+        self.bus.write_byte(CONVERT_T)?;
+
+        // Sleeps rather than spinning, so other tasks run meanwhile.
+        thread::sleep(Duration::from_millis(
+            self.resolution.conversion_time() as u64
+        ));
+
+        Ok(())
+    }
+
+    pub fn read_temp(&mut self, rom: &Rom) -> Result<f32> {
+        let scratchpad = self.read_scratchpad(rom)?;
+        Ok((scratchpad.raw & self.resolution.mask()) as f32 / 16.0)
+    }
+
+    pub fn get_temps(&mut self) -> Vec<(Rom, Result<f32>)> {
+        let converted = self.convert();
+
+        self.roms
+            .clone()
+            .into_iter()
+            .map(|rom| match &converted {
+                Ok(_) => (rom, self.read_temp(&rom)),
+                Err(e) => (rom, Err(anyhow!("Failed to convert: {:?}", e))),
+            })
+            .collect()
+    }
+
+    pub fn set_alarm(&mut self, rom: &Rom, high: f32, low: f32) -> Result<()> {
+        let scratchpad = self.read_scratchpad(rom)?;
+        let high = high.round().clamp(i8::MIN as f32, i8::MAX as f32) as i8;
+        let low = low.round().clamp(i8::MIN as f32, i8::MAX as f32) as i8;
+
+        self.write_scratchpad(rom, high, low, scratchpad.config)?;
+
+        self.bus.select(Some(rom))?;
+        self.bus.write_byte(COPY_SCRATCHPAD)?;
+        thread::sleep(Duration::from_millis(EEPROM_WRITE_TIME as u64));
+
+        Ok(())
+    }
+
+    pub fn get_alarm(&mut self, rom: &Rom) -> Result<(f32, f32)> {
+        let scratchpad = self.read_scratchpad(rom)?;
+        Ok((scratchpad.high as f32, scratchpad.low as f32))
+    }
+
+    pub fn search_alarm(&mut self) -> Result<Vec<Rom>> {
+        self.bus.search(ALARM_SEARCH)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::drivers::sim::{Bus, Delay, Pin, Probe};
+    use std::{cell::RefCell, rc::Rc};
+
+    fn sensor(bus: &Rc<RefCell<Bus>>) -> Result<Ds18b20<Pin, Delay>> {
+        Ds18b20::new(OneWire::new(Pin(bus.clone()), Delay(bus.clone()))?)
+    }
+
+    #[test]
+    fn reads_every_probe() {
+        // 25.0625 °C, 32 °C and -10.125 °C.
+        let bus = Bus::new(vec![
+            Probe::new(1, 0x0191),
+            Probe::new(2, 0x0200),
+            Probe::new(3, -0x00A2),
+        ]);
+        let mut ds18b20 = sensor(&bus).unwrap();
+
+        for (rom, temperature) in ds18b20.get_temps() {
+            let probe = bus
+                .borrow()
+                .probes
+                .iter()
+                .find(|probe| probe.rom == rom)
+                .map(|probe| probe.raw)
+                .unwrap();
+
+            assert_eq!(temperature.unwrap(), probe as f32 / 16.0);
+        }
+
+        assert_eq!(ds18b20.roms().len(), 3);
+    }
+
+    #[test]
+    fn sets_the_resolution_on_begin() {
+        let bus = Bus::new(vec![Probe::new(1, 0)]);
+        {
+            let scratchpad = &mut bus.borrow_mut().probes[0].scratchpad;
+            scratchpad[4] = 0x1F;
+            scratchpad[8] = crc8(&scratchpad[..8]);
+        }
+
+        sensor(&bus).unwrap();
+
+        assert_eq!(bus.borrow().probes[0].scratchpad[4], 0x7F);
+    }
+
+    #[test]
+    fn fails_without_probes() {
+        assert!(sensor(&Bus::new(Vec::new())).is_err());
+    }
+
+    #[test]
+    fn rejects_a_corrupted_scratchpad() {
+        let bus = Bus::new(vec![Probe::new(1, 0x0191)]);
+        let mut ds18b20 = sensor(&bus).unwrap();
+        let rom = ds18b20.roms()[0];
+        bus.borrow_mut().probes[0].scratchpad[0] ^= 0x01;
+
+        let error = ds18b20.read_temp(&rom).unwrap_err();
+        assert!(error.to_string().contains("crc"));
+    }
+
+    #[test]
+    fn rejects_an_empty_scratchpad() {
+        let bus = Bus::new(vec![Probe::new(1, 0x0191)]);
+        let mut ds18b20 = sensor(&bus).unwrap();
+        let rom = ds18b20.roms()[0];
+        // All zeros carry a valid crc, so only the explicit check catches them.
+        bus.borrow_mut().probes[0].scratchpad = [0; 9];
+
+        let error = ds18b20.read_temp(&rom).unwrap_err();
+        assert!(error.to_string().contains("Empty"));
+    }
+
+    #[test]
+    fn stores_alarm_thresholds() {
+        let bus = Bus::new(vec![Probe::new(1, 0x0200), Probe::new(2, 0x0100)]);
+        let mut ds18b20 = sensor(&bus).unwrap();
+        let hot = bus.borrow().probes[0].rom;
+
+        ds18b20.set_alarm(&hot, 30.4, -5.6).unwrap();
+
+        assert_eq!(ds18b20.get_alarm(&hot).unwrap(), (30.0, -6.0));
+        assert_eq!(bus.borrow().probes[0].copies, 1);
+
+        ds18b20.convert().unwrap();
+        assert_eq!(ds18b20.search_alarm().unwrap(), vec![hot]);
+    }
+}
@@ -0,0 +1,6 @@
+pub mod ds18b20;
+pub mod mpu6050;
+pub mod onewire;
+
+#[cfg(test)]
+mod sim;
@@ -0,0 +1,471 @@
+use crate::analysis::fall::Vector;
+use anyhow::Result;
+use embedded_hal::blocking::i2c::{Write, WriteRead};
+use std::error::Error;
+
+pub const ADDRESS: u8 = 0x68;
+
+const SMPLRT_DIV: u8 = 0x19;
+const CONFIG: u8 = 0x1A;
+const GYRO_CONFIG: u8 = 0x1B;
+const ACCEL_CONFIG: u8 = 0x1C;
+const MOT_THR: u8 = 0x1F;
+const MOT_DUR: u8 = 0x20;
+const INT_PIN_CFG: u8 = 0x37;
+const INT_ENABLE: u8 = 0x38;
+const INT_STATUS: u8 = 0x3A;
+const ACCEL_XOUT_H: u8 = 0x3B;
+const TEMP_OUT_H: u8 = 0x41;
+const GYRO_XOUT_H: u8 = 0x43;
+const MOT_DETECT_CTRL: u8 = 0x69;
+const PWR_MGMT_1: u8 = 0x6B;
+const PWR_MGMT_2: u8 = 0x6C;
+
+const MOT_INT: u8 = 0x40;
+const LATCH_INT_EN: u8 = 0x20;
+const ACCEL_ON_DELAY: u8 = 0x30;
+const ACCEL_HPF_5HZ: u8 = 0x01;
+const CLOCK_PLL_XGYRO: u8 = 0x01;
+const CYCLE: u8 = 0x20;
+const TEMP_DIS: u8 = 0x08;
+const STBY_GYRO: u8 = 0x07;
+
+// Motion threshold register counts 2 mg per LSB.
+const MG_PER_THRESHOLD: u16 = 2;
+const TEMP_SENSITIVITY: f32 = 340.0;
+const TEMP_OFFSET: f32 = 36.53;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AccelRange {
+    G2,
+    G4,
+    G8,
+    G16,
+}
+
+impl AccelRange {
+    fn bits(&self) -> u8 {
+        (*self as u8) << 3
+    }
+
+    pub fn lsb_per_g(&self) -> f32 {
+        match self {
+            AccelRange::G2 => 16384.0,
+            AccelRange::G4 => 8192.0,
+            AccelRange::G8 => 4096.0,
+            AccelRange::G16 => 2048.0,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GyroRange {
+    Dps250,
+    Dps500,
+    Dps1000,
+    Dps2000,
+}
+
+impl GyroRange {
+    fn bits(&self) -> u8 {
+        (*self as u8) << 3
+    }
+
+    pub fn lsb_per_dps(&self) -> f32 {
+        match self {
+            GyroRange::Dps250 => 131.0,
+            GyroRange::Dps500 => 65.5,
+            GyroRange::Dps1000 => 32.8,
+            GyroRange::Dps2000 => 16.4,
+        }
+    }
+}
+
+/// Digital low-pass filter bandwidth, applied to both accelerometer and gyroscope.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Filter {
+    Hz260,
+    Hz184,
+    Hz94,
+    Hz44,
+    Hz21,
+    Hz10,
+    Hz5,
+}
+
+/// Sampling rate of the accelerometer while sleeping in wake-on-motion mode.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum WakeFrequency {
+    Hz1_25,
+    Hz5,
+    Hz20,
+    Hz40,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Motion {
+    pub threshold: u16,
+    pub duration: u8,
+}
+
+impl Motion {
+    /// `threshold` in mg, `duration` in ms (1 ms per count).
+    pub fn new(threshold: u16, duration: u8) -> Self {
+        Self {
+            threshold,
+            duration,
+        }
+    }
+
+    fn threshold_bits(&self) -> u8 {
+        (self.threshold / MG_PER_THRESHOLD).min(u8::MAX as u16) as u8
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Config {
+    pub accel: AccelRange,
+    pub gyro: GyroRange,
+    pub filter: Filter,
+}
+
+impl Default for Config {
+    // The DMP integrates the gyroscope assuming the ±2000 °/s range.
+    fn default() -> Self {
+        Self {
+            accel: AccelRange::G2,
+            gyro: GyroRange::Dps2000,
+            filter: Filter::Hz44,
+        }
+    }
+}
+
+/// Register-level access to the sensor, independent of the DMP firmware.
+pub struct Registers<I2C> {
+    i2c: I2C,
+    address: u8,
+}
+
+impl<I2C> Registers<I2C>
+where
+    I2C: Write + WriteRead,
+    <I2C as Write>::Error: Error + Send + Sync + 'static,
+    <I2C as WriteRead>::Error: Error + Send + Sync + 'static,
+{
+    pub fn new(i2c: I2C, address: u8) -> Self {
+        Self { i2c, address }
+    }
+
+    pub fn read(&mut self, register: u8, buf: &mut [u8]) -> Result<()> {
+        self.i2c.write_read(self.address, &[register], buf)?;
+        Ok(())
+    }
+
+    pub fn read_u8(&mut self, register: u8) -> Result<u8> {
+        let mut buf = [0; 1];
+        self.read(register, &mut buf)?;
+        Ok(buf[0])
+    }
+
+    pub fn write_u8(&mut self, register: u8, value: u8) -> Result<()> {
+        self.i2c.write(self.address, &[register, value])?;
+        Ok(())
+    }
+
+    pub fn update(&mut self, register: u8, mask: u8, value: u8) -> Result<()> {
+        let current = self.read_u8(register)?;
+        self.write_u8(register, (current & !mask) | (value & mask))
+    }
+
+    pub fn read_i16x3(&mut self, register: u8) -> Result<[i16; 3]> {
+        let mut buf = [0; 6];
+        self.read(register, &mut buf)?;
+
+        Ok([
+            i16::from_be_bytes([buf[0], buf[1]]),
+            i16::from_be_bytes([buf[2], buf[3]]),
+            i16::from_be_bytes([buf[4], buf[5]]),
+        ])
+    }
+}
+
+/// Configuration, raw and converted readings and motion interrupts.
+pub struct Imu<I2C> {
+    registers: Registers<I2C>,
+    config: Config,
+}
+
+impl<I2C> Imu<I2C>
+where
+    I2C: Write + WriteRead,
+    <I2C as Write>::Error: Error + Send + Sync + 'static,
+    <I2C as WriteRead>::Error: Error + Send + Sync + 'static,
+{
+    pub fn new(i2c: I2C, address: u8) -> Self {
+        Self {
+            registers: Registers::new(i2c, address),
+            config: Config::default(),
+        }
+    }
+
+    pub fn config(&self) -> Config {
+        self.config
+    }
+
+    pub fn configure(&mut self, config: Config) -> Result<()> {
+        self.set_accel_range(config.accel)?;
+        self.set_gyro_range(config.gyro)?;
+        self.set_filter(config.filter)
+    }
+
+    pub fn set_accel_range(&mut self, range: AccelRange) -> Result<()> {
+        self.registers.update(ACCEL_CONFIG, 0x18, range.bits())?;
+        self.config.accel = range;
+        Ok(())
+    }
+
+    pub fn set_gyro_range(&mut self, range: GyroRange) -> Result<()> {
+        self.registers.update(GYRO_CONFIG, 0x18, range.bits())?;
+        self.config.gyro = range;
+        Ok(())
+    }
+
+    pub fn set_filter(&mut self, filter: Filter) -> Result<()> {
+        self.registers.update(CONFIG, 0x07, filter as u8)?;
+        self.config.filter = filter;
+        Ok(())
+    }
+
+    /// Output rate is `gyro rate / (1 + divider)`, the gyro rate being 1 kHz with the filter on.
+    pub fn set_sample_divider(&mut self, divider: u8) -> Result<()> {
+        self.registers.write_u8(SMPLRT_DIV, divider)
+    }
+
+    pub fn accel(&mut self) -> Result<[i16; 3]> {
+        self.registers.read_i16x3(ACCEL_XOUT_H)
+    }
+
+    pub fn gyro(&mut self) -> Result<[i16; 3]> {
+        self.registers.read_i16x3(GYRO_XOUT_H)
+    }
+
+    /// Acceleration in g.
+    pub fn accel_g(&mut self) -> Result<Vector> {
+        let [x, y, z] = self.accel()?;
+        let lsb = self.config.accel.lsb_per_g();
+        Ok(Vector::new(x as f32 / lsb, y as f32 / lsb, z as f32 / lsb))
+    }
+
+    /// Angular rate in °/s.
+    pub fn gyro_dps(&mut self) -> Result<Vector> {
+        let [x, y, z] = self.gyro()?;
+        let lsb = self.config.gyro.lsb_per_dps();
+        Ok(Vector::new(x as f32 / lsb, y as f32 / lsb, z as f32 / lsb))
+    }
+
+    /// Die temperature in °C.
+    pub fn temperature(&mut self) -> Result<f32> {
+        let mut buf = [0; 2];
+        self.registers.read(TEMP_OUT_H, &mut buf)?;
+        Ok(i16::from_be_bytes(buf) as f32 / TEMP_SENSITIVITY + TEMP_OFFSET)
+    }
+
+    /// Latches the INT pin when acceleration exceeds the threshold for the given duration.
+    pub fn enable_motion(&mut self, motion: Motion) -> Result<()> {
+        self.registers.update(ACCEL_CONFIG, 0x07, ACCEL_HPF_5HZ)?;
+        self.registers.write_u8(MOT_THR, motion.threshold_bits())?;
+        self.registers.write_u8(MOT_DUR, motion.duration)?;
+        self.registers.write_u8(MOT_DETECT_CTRL, ACCEL_ON_DELAY)?;
+        self.registers
+            .update(INT_PIN_CFG, LATCH_INT_EN, LATCH_INT_EN)?;
+        self.registers.update(INT_ENABLE, MOT_INT, MOT_INT)
+    }
+
+    pub fn disable_motion(&mut self) -> Result<()> {
+        self.registers.update(INT_ENABLE, MOT_INT, 0)?;
+        self.registers.update(ACCEL_CONFIG, 0x07, 0)
+    }
+
+    /// Reading the status clears the latched interrupt.
+    pub fn motion_detected(&mut self) -> Result<bool> {
+        Ok(self.registers.read_u8(INT_STATUS)? & MOT_INT != 0)
+    }
+
+    /// Puts the gyroscope in standby and cycles the accelerometer at `frequency`,
+    /// raising the motion interrupt to wake the host.
+    pub fn wake_on_motion(&mut self, motion: Motion, frequency: WakeFrequency) -> Result<()> {
+        self.set_filter(Filter::Hz260)?;
+        self.enable_motion(motion)?;
+        self.registers
+            .write_u8(PWR_MGMT_2, ((frequency as u8) << 6) | STBY_GYRO)?;
+        self.registers
+            .write_u8(PWR_MGMT_1, CYCLE | TEMP_DIS | CLOCK_PLL_XGYRO)
+    }
+
+    /// Leaves low-power cycling and restores the previous configuration.
+    pub fn wake(&mut self, config: Config) -> Result<()> {
+        self.registers.write_u8(PWR_MGMT_1, CLOCK_PLL_XGYRO)?;
+        self.registers.write_u8(PWR_MGMT_2, 0)?;
+        self.disable_motion()?;
+        self.configure(config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{cell::RefCell, convert::Infallible, rc::Rc};
+
+    /// The register file of one sensor. Reading the interrupt status clears
+    /// it, as on the device.
+    #[derive(Clone)]
+    struct Bus(Rc<RefCell<Vec<u8>>>);
+
+    impl Bus {
+        fn new() -> Self {
+            Self(Rc::new(RefCell::new(vec![0; 128])))
+        }
+
+        fn get(&self, register: u8) -> u8 {
+            self.0.borrow()[register as usize]
+        }
+
+        fn set(&self, register: u8, values: &[u8]) {
+            let start = register as usize;
+            self.0.borrow_mut()[start..start + values.len()].copy_from_slice(values);
+        }
+    }
+
+    impl Write for Bus {
+        type Error = Infallible;
+
+        fn write(&mut self, address: u8, bytes: &[u8]) -> Result<(), Infallible> {
+            assert_eq!(address, ADDRESS);
+            self.set(bytes[0], &bytes[1..]);
+            Ok(())
+        }
+    }
+
+    impl WriteRead for Bus {
+        type Error = Infallible;
+
+        fn write_read(
+            &mut self,
+            address: u8,
+            bytes: &[u8],
+            buffer: &mut [u8],
+        ) -> Result<(), Infallible> {
+            assert_eq!(address, ADDRESS);
+            let start = bytes[0] as usize;
+            buffer.copy_from_slice(&self.0.borrow()[start..start + buffer.len()]);
+
+            if bytes[0] == INT_STATUS {
+                self.set(INT_STATUS, &[0]);
+            }
+
+            Ok(())
+        }
+    }
+
+    fn imu() -> (Bus, Imu<Bus>) {
+        let bus = Bus::new();
+        (bus.clone(), Imu::new(bus, ADDRESS))
+    }
+
+    #[test]
+    fn configures_only_the_range_and_filter_bits() {
+        let (bus, mut imu) = imu();
+        bus.set(ACCEL_CONFIG, &[0x01]);
+        bus.set(CONFIG, &[0x40]);
+
+        imu.configure(Config {
+            accel: AccelRange::G8,
+            gyro: GyroRange::Dps500,
+            filter: Filter::Hz21,
+        })
+        .unwrap();
+
+        assert_eq!(bus.get(ACCEL_CONFIG), 0x11);
+        assert_eq!(bus.get(GYRO_CONFIG), 0x08);
+        assert_eq!(bus.get(CONFIG), 0x44);
+        assert_eq!(imu.config().accel, AccelRange::G8);
+    }
+
+    #[test]
+    fn scales_readings_by_the_range() {
+        let (bus, mut imu) = imu();
+        bus.set(ACCEL_XOUT_H, &[0x30, 0x00, 0xF0, 0x00, 0x00, 0x00]);
+        bus.set(GYRO_XOUT_H, &[0x00, 0x83, 0xFF, 0x7D, 0x00, 0x00]);
+
+        imu.set_accel_range(AccelRange::G8).unwrap();
+        imu.set_gyro_range(GyroRange::Dps250).unwrap();
+
+        // An impact of 3 g, past what the default ±2 g range can hold.
+        let accel = imu.accel_g().unwrap();
+        assert_eq!((accel.x, accel.y, accel.z), (3.0, -1.0, 0.0));
+
+        let gyro = imu.gyro_dps().unwrap();
+        assert_eq!((gyro.x, gyro.y, gyro.z), (1.0, -1.0, 0.0));
+    }
+
+    #[test]
+    fn reads_the_die_temperature() {
+        let (bus, mut imu) = imu();
+        bus.set(TEMP_OUT_H, &(-521i16).to_be_bytes());
+
+        assert!((imu.temperature().unwrap() - 35.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn sleeps_until_motion() {
+        let (bus, mut imu) = imu();
+        imu.configure(Config::default()).unwrap();
+
+        imu.wake_on_motion(Motion::new(40, 5), WakeFrequency::Hz20)
+            .unwrap();
+
+        assert_eq!(bus.get(MOT_THR), 20);
+        assert_eq!(bus.get(MOT_DUR), 5);
+        assert_eq!(bus.get(INT_ENABLE) & MOT_INT, MOT_INT);
+        assert_eq!(bus.get(INT_PIN_CFG) & LATCH_INT_EN, LATCH_INT_EN);
+        assert_eq!(bus.get(ACCEL_CONFIG) & 0x07, ACCEL_HPF_5HZ);
+        assert_eq!(bus.get(PWR_MGMT_2), 0x80 | STBY_GYRO);
+        assert_eq!(bus.get(PWR_MGMT_1), CYCLE | TEMP_DIS | CLOCK_PLL_XGYRO);
+        assert_eq!(bus.get(CONFIG), Filter::Hz260 as u8);
+    }
+
+    #[test]
+    fn reading_the_motion_status_clears_it() {
+        let (bus, mut imu) = imu();
+        bus.set(INT_STATUS, &[MOT_INT]);
+
+        assert!(imu.motion_detected().unwrap());
+        assert!(!imu.motion_detected().unwrap());
+    }
+
+    #[test]
+    fn wakes_with_the_previous_configuration() {
+        let (bus, mut imu) = imu();
+        let config = Config {
+            accel: AccelRange::G8,
+            ..Config::default()
+        };
+
+        imu.wake_on_motion(Motion::new(40, 5), WakeFrequency::Hz5)
+            .unwrap();
+        imu.wake(config).unwrap();
+
+        assert_eq!(bus.get(PWR_MGMT_1), CLOCK_PLL_XGYRO);
+        assert_eq!(bus.get(PWR_MGMT_2), 0);
+        assert_eq!(bus.get(INT_ENABLE), 0);
+        assert_eq!(bus.get(ACCEL_CONFIG), AccelRange::G8.bits());
+        assert_eq!(bus.get(GYRO_CONFIG), GyroRange::Dps2000.bits());
+        assert_eq!(bus.get(CONFIG), Filter::Hz44 as u8);
+    }
+
+    #[test]
+    fn caps_the_motion_threshold() {
+        assert_eq!(Motion::new(40, 1).threshold_bits(), 20);
+        assert_eq!(Motion::new(u16::MAX, 1).threshold_bits(), u8::MAX);
+    }
+}
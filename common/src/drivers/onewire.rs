@@ -0,0 +1,284 @@
+use anyhow::{anyhow, Result};
+use embedded_hal::{
+    blocking::delay::{DelayMs, DelayUs},
+    digital::v2::{InputPin, OutputPin},
+};
+use std::fmt::Debug;
+
+pub const SEARCH_ROM: u8 = 0xF0;
+pub const ALARM_SEARCH: u8 = 0xEC;
+pub const MATCH_ROM: u8 = 0x55;
+pub const SKIP_ROM: u8 = 0xCC;
+
+const MAX_DEVICES: usize = 8;
+
+pub type Rom = [u8; 8];
+
+pub fn crc8(data: &[u8]) -> u8 {
+    let mut crc = 0u8;
+
+    for byte in data {
+        let mut byte = *byte;
+
+        for _ in 0..8 {
+            let mix = (crc ^ byte) & 0x01;
+            crc >>= 1;
+
+            if mix != 0 {
+                crc ^= 0x8C;
+            }
+
+            byte >>= 1;
+        }
+    }
+
+    crc
+}
+
+pub fn rom_id(rom: &Rom) -> String {
+    rom.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+pub struct OneWire<P, D> {
+    pin: P,
+    delay: D,
+}
+
+impl<P, D, E> OneWire<P, D>
+where
+    P: InputPin<Error = E> + OutputPin<Error = E>,
+    D: DelayUs<u16> + DelayMs<u16>,
+    E: Debug,
+{
+    pub fn new(mut pin: P, delay: D) -> Result<Self> {
+        pin.set_high()
+            .map_err(|e| anyhow!("Failed to release bus: {:?}", e))?;
+        Ok(Self { pin, delay })
+    }
+
+    fn low(&mut self) -> Result<()> {
+        self.pin
+            .set_low()
+            .map_err(|e| anyhow!("Failed to pull bus low: {:?}", e))
+    }
+
+    fn release(&mut self) -> Result<()> {
+        self.pin
+            .set_high()
+            .map_err(|e| anyhow!("Failed to release bus: {:?}", e))
+    }
+
+    fn sample(&mut self) -> Result<bool> {
+        self.pin
+            .is_high()
+            .map_err(|e| anyhow!("Failed to read bus: {:?}", e))
+    }
+
+    pub fn delay_ms(&mut self, ms: u16) {
+        self.delay.delay_ms(ms);
+    }
+
+    pub fn reset(&mut self) -> Result<bool> {
+        self.low()?;
+        self.delay.delay_us(480);
+
+        let presence = critical_section::with(|_| {
+            self.release()?;
+            self.delay.delay_us(70);
+            self.sample().map(|high| !high)
+        })?;
+
+        self.delay.delay_us(410);
+        Ok(presence)
+    }
+
+    pub fn write_bit(&mut self, bit: bool) -> Result<()> {
+        critical_section::with(|_| {
+            self.low()?;
+            self.delay.delay_us(if bit { 6 } else { 60 });
+            self.release()?;
+            self.delay.delay_us(if bit { 64 } else { 10 });
+            Ok(())
+        })
+    }
+
+    pub fn read_bit(&mut self) -> Result<bool> {
+        let bit = critical_section::with(|_| {
+            self.low()?;
+            self.delay.delay_us(6);
+            self.release()?;
+            self.delay.delay_us(9);
+            self.sample()
+        })?;
+
+        self.delay.delay_us(55);
+        Ok(bit)
+    }
+
+    pub fn write_byte(&mut self, byte: u8) -> Result<()> {
+        for i in 0..8 {
+            self.write_bit(byte & (1 << i) != 0)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn read_byte(&mut self) -> Result<u8> {
+        let mut byte = 0;
+
+        for i in 0..8 {
+            if self.read_bit()? {
+                byte |= 1 << i;
+            }
+        }
+
+        Ok(byte)
+    }
+
+    pub fn write_bytes(&mut self, bytes: &[u8]) -> Result<()> {
+        for byte in bytes {
+            self.write_byte(*byte)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn read_bytes(&mut self, bytes: &mut [u8]) -> Result<()> {
+        for byte in bytes.iter_mut() {
+            *byte = self.read_byte()?;
+        }
+
+        Ok(())
+    }
+
+    pub fn select(&mut self, rom: Option<&Rom>) -> Result<()> {
+        if !self.reset()? {
+            return Err(anyhow!("No device present on the bus"));
+        }
+
+        match rom {
+            Some(rom) => {
+                self.write_byte(MATCH_ROM)?;
+                self.write_bytes(rom)
+            }
+            None => self.write_byte(SKIP_ROM),
+        }
+    }
+
+    pub fn search(&mut self, command: u8) -> Result<Vec<Rom>> {
+        let mut roms = Vec::new();
+        let mut rom: Rom = [0; 8];
+        let mut last_discrepancy = 0;
+
+        loop {
+            if !self.reset()? {
+                return Ok(roms);
+            }
+
+            self.write_byte(command)?;
+            let mut last_zero = 0;
+
+            for bit in 1..=64 {
+                let id = self.read_bit()?;
+                let complement = self.read_bit()?;
+
+                if id && complement {
+                    return Ok(roms);
+                }
+
+                let (byte, mask) = ((bit - 1) / 8, 1u8 << ((bit - 1) % 8));
+                let direction = if id != complement {
+                    id
+                } else if bit < last_discrepancy {
+                    rom[byte] & mask != 0
+                } else {
+                    bit == last_discrepancy
+                };
+
+                if !id && !complement && !direction {
+                    last_zero = bit;
+                }
+
+                if direction {
+                    rom[byte] |= mask;
+                } else {
+                    rom[byte] &= !mask;
+                }
+
+                self.write_bit(direction)?;
+            }
+
+            if crc8(&rom[..7]) != rom[7] {
+                return Err(anyhow!("Invalid rom crc {}", rom_id(&rom)));
+            }
+
+            roms.push(rom);
+            last_discrepancy = last_zero;
+
+            if last_discrepancy == 0 || roms.len() >= MAX_DEVICES {
+                return Ok(roms);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::drivers::sim::{Bus, Delay, Pin, Probe};
+
+    fn bus(probes: Vec<Probe>) -> OneWire<Pin, Delay> {
+        let bus = Bus::new(probes);
+        OneWire::new(Pin(bus.clone()), Delay(bus)).unwrap()
+    }
+
+    #[test]
+    fn crc_of_data_and_its_crc_is_zero() {
+        let rom = Probe::new(0x5A, 0).rom;
+
+        assert_eq!(crc8(&rom), 0);
+        assert_ne!(crc8(&rom[..7]), 0);
+    }
+
+    #[test]
+    fn detects_presence() {
+        assert!(bus(vec![Probe::new(1, 0)]).reset().unwrap());
+        assert!(!bus(Vec::new()).reset().unwrap());
+    }
+
+    #[test]
+    fn finds_every_rom() {
+        // Serials sharing most of their bits, so the search branches deep.
+        let probes = vec![
+            Probe::new(0b1010, 0),
+            Probe::new(0b0110, 0),
+            Probe::new(0b1011, 0),
+        ];
+        let mut expected = probes.iter().map(|probe| probe.rom).collect::<Vec<_>>();
+        let mut found = bus(probes).search(SEARCH_ROM).unwrap();
+
+        expected.sort();
+        found.sort();
+        assert_eq!(found, expected);
+    }
+
+    #[test]
+    fn finds_nothing_on_an_empty_bus() {
+        assert!(bus(Vec::new()).search(SEARCH_ROM).unwrap().is_empty());
+    }
+
+    #[test]
+    fn rejects_a_rom_with_a_bad_crc() {
+        let mut probe = Probe::new(3, 0);
+        probe.rom[7] ^= 0xFF;
+
+        assert!(bus(vec![probe]).search(SEARCH_ROM).is_err());
+    }
+
+    #[test]
+    fn formats_rom_ids() {
+        let rom = [0x28, 0x01, 0xAB, 0, 0, 0, 0, 0xFF];
+
+        assert_eq!(rom_id(&rom), "2801ab00000000ff");
+    }
+}
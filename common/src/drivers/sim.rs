@@ -0,0 +1,260 @@
+//! A 1-Wire bus with DS18B20 probes on it, simulated slot by slot from the
+//! pin levels and delays the driver produces.
+
+use crate::drivers::onewire::{crc8, Rom, ALARM_SEARCH, MATCH_ROM, SEARCH_ROM, SKIP_ROM};
+use embedded_hal::{
+    blocking::delay::{DelayMs, DelayUs},
+    digital::v2::{InputPin, OutputPin},
+};
+use std::{cell::RefCell, convert::Infallible, rc::Rc};
+
+const RESET_US: u64 = 480;
+// Pulses shorter than this write a one or start a read slot.
+const SHORT_US: u64 = 15;
+// What the scratchpad holds before the first conversion, 85 °C.
+const POWER_ON: i16 = 0x0550;
+
+const CONVERT_T: u8 = 0x44;
+const WRITE_SCRATCHPAD: u8 = 0x4E;
+const READ_SCRATCHPAD: u8 = 0xBE;
+const COPY_SCRATCHPAD: u8 = 0x48;
+
+#[derive(Clone, Debug, PartialEq)]
+enum State {
+    Idle,
+    Command(Vec<bool>),
+    Search { bit: usize, step: u8 },
+    Match(Vec<bool>),
+    Function(Vec<bool>),
+    Sending(Vec<bool>),
+    Receiving(Vec<bool>),
+}
+
+pub struct Probe {
+    pub rom: Rom,
+    /// Temperature in 1/16 °C, taken on the next conversion.
+    pub raw: i16,
+    pub scratchpad: [u8; 9],
+    pub copies: u32,
+    alarm: bool,
+    state: State,
+}
+
+impl Probe {
+    pub fn new(serial: u8, raw: i16) -> Self {
+        let mut rom = [0x28, serial, 0x2A, 0x17, 0x00, 0x00, 0x00, 0x00];
+        rom[7] = crc8(&rom[..7]);
+
+        // Thresholds as wide as the probe's range, so no alarm until set.
+        let [low, high] = POWER_ON.to_le_bytes();
+        let mut scratchpad = [low, high, 125, -55i8 as u8, 0x7F, 0xFF, 0x0C, 0x10, 0];
+        scratchpad[8] = crc8(&scratchpad[..8]);
+
+        Self {
+            rom,
+            raw,
+            scratchpad,
+            copies: 0,
+            alarm: false,
+            state: State::Idle,
+        }
+    }
+
+    fn rom_bit(&self, bit: usize) -> bool {
+        self.rom[bit / 8] & (1 << (bit % 8)) != 0
+    }
+
+    /// The level this probe leaves on the line during the next slot.
+    fn output(&self) -> bool {
+        match &self.state {
+            State::Search { bit, step: 0 } => self.rom_bit(*bit),
+            State::Search { bit, step: 1 } => !self.rom_bit(*bit),
+            State::Sending(bits) => bits.first().copied().unwrap_or(true),
+            _ => true,
+        }
+    }
+
+    fn slot(&mut self, line: bool) {
+        self.state = match std::mem::replace(&mut self.state, State::Idle) {
+            State::Idle => State::Idle,
+            State::Command(mut bits) => {
+                bits.push(line);
+                match byte(&bits) {
+                    Some(SEARCH_ROM) => State::Search { bit: 0, step: 0 },
+                    Some(ALARM_SEARCH) if self.alarm => State::Search { bit: 0, step: 0 },
+                    Some(MATCH_ROM) => State::Match(Vec::new()),
+                    Some(SKIP_ROM) => State::Function(Vec::new()),
+                    Some(_) => State::Idle,
+                    None => State::Command(bits),
+                }
+            }
+            State::Search { bit, step: 2 } if line != self.rom_bit(bit) => State::Idle,
+            State::Search { bit: 63, step: 2 } => State::Idle,
+            State::Search { bit, step: 2 } => State::Search {
+                bit: bit + 1,
+                step: 0,
+            },
+            State::Search { bit, step } => State::Search {
+                bit,
+                step: step + 1,
+            },
+            State::Match(mut bits) => {
+                bits.push(line);
+
+                if bits.len() < 64 {
+                    State::Match(bits)
+                } else if bits.chunks(8).filter_map(byte).eq(self.rom) {
+                    State::Function(Vec::new())
+                } else {
+                    State::Idle
+                }
+            }
+            State::Function(mut bits) => {
+                bits.push(line);
+                match byte(&bits) {
+                    Some(command) => self.function(command),
+                    None => State::Function(bits),
+                }
+            }
+            State::Sending(mut bits) => {
+                bits.remove(0);
+                State::Sending(bits)
+            }
+            State::Receiving(mut bits) => {
+                bits.push(line);
+
+                if bits.len() < 24 {
+                    State::Receiving(bits)
+                } else {
+                    for (i, value) in bits.chunks(8).filter_map(byte).enumerate() {
+                        self.scratchpad[2 + i] = value;
+                    }
+
+                    self.scratchpad[8] = crc8(&self.scratchpad[..8]);
+                    State::Idle
+                }
+            }
+        };
+    }
+
+    fn function(&mut self, command: u8) -> State {
+        match command {
+            CONVERT_T => {
+                let [low, high] = self.raw.to_le_bytes();
+                self.scratchpad[0] = low;
+                self.scratchpad[1] = high;
+                self.scratchpad[8] = crc8(&self.scratchpad[..8]);
+
+                let degrees = self.raw >> 4;
+                self.alarm = degrees >= self.scratchpad[2] as i8 as i16
+                    || degrees <= self.scratchpad[3] as i8 as i16;
+                State::Idle
+            }
+            READ_SCRATCHPAD => State::Sending(
+                self.scratchpad
+                    .iter()
+                    .flat_map(|byte| (0..8).map(move |i| byte & (1 << i) != 0))
+                    .collect(),
+            ),
+            WRITE_SCRATCHPAD => State::Receiving(Vec::new()),
+            COPY_SCRATCHPAD => {
+                self.copies += 1;
+                State::Idle
+            }
+            _ => State::Idle,
+        }
+    }
+}
+
+fn byte(bits: &[bool]) -> Option<u8> {
+    (bits.len() == 8).then(|| {
+        bits.iter()
+            .enumerate()
+            .fold(0, |byte, (i, bit)| byte | (*bit as u8) << i)
+    })
+}
+
+#[derive(Default)]
+pub struct Bus {
+    pub probes: Vec<Probe>,
+    time_us: u64,
+    low_since: Option<u64>,
+    line: bool,
+}
+
+impl Bus {
+    pub fn new(probes: Vec<Probe>) -> Rc<RefCell<Self>> {
+        Rc::new(RefCell::new(Self {
+            probes,
+            ..Self::default()
+        }))
+    }
+
+    fn release(&mut self) {
+        let Some(since) = self.low_since.take() else {
+            return;
+        };
+
+        if self.time_us - since >= RESET_US {
+            for probe in self.probes.iter_mut() {
+                probe.state = State::Command(Vec::new());
+            }
+
+            // A present device holds the line low after the reset.
+            self.line = self.probes.is_empty();
+            return;
+        }
+
+        let written = self.time_us - since < SHORT_US;
+        self.line = written && self.probes.iter().all(Probe::output);
+
+        for probe in self.probes.iter_mut() {
+            probe.slot(self.line);
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct Pin(pub Rc<RefCell<Bus>>);
+
+impl OutputPin for Pin {
+    type Error = Infallible;
+
+    fn set_low(&mut self) -> Result<(), Infallible> {
+        let mut bus = self.0.borrow_mut();
+        bus.low_since = Some(bus.time_us);
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), Infallible> {
+        self.0.borrow_mut().release();
+        Ok(())
+    }
+}
+
+impl InputPin for Pin {
+    type Error = Infallible;
+
+    fn is_high(&self) -> Result<bool, Infallible> {
+        Ok(self.0.borrow().line)
+    }
+
+    fn is_low(&self) -> Result<bool, Infallible> {
+        Ok(!self.0.borrow().line)
+    }
+}
+
+#[derive(Clone)]
+pub struct Delay(pub Rc<RefCell<Bus>>);
+
+impl DelayUs<u16> for Delay {
+    fn delay_us(&mut self, us: u16) {
+        self.0.borrow_mut().time_us += us as u64;
+    }
+}
+
+impl DelayMs<u16> for Delay {
+    fn delay_ms(&mut self, ms: u16) {
+        self.0.borrow_mut().time_us += ms as u64 * 1000;
+    }
+}
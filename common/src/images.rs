@@ -0,0 +1,68 @@
+/// Wi-Fi signal, 13x10, from one bar to four.
+pub const WIFI: [[u8; 20]; 4] = [
+    [
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x60,
+        0x00, 0x60, 0x00, 0x6d, 0xb0,
+    ],
+    [
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0c, 0x00, 0x0c, 0x00, 0x6c,
+        0x00, 0x6c, 0x00, 0x6d, 0xb0,
+    ],
+    [
+        0x00, 0x00, 0x00, 0x00, 0x01, 0x80, 0x01, 0x80, 0x01, 0x80, 0x0d, 0x80, 0x0d, 0x80, 0x6d,
+        0x80, 0x6d, 0x80, 0x6d, 0xb0,
+    ],
+    [
+        0x00, 0x30, 0x00, 0x30, 0x01, 0xb0, 0x01, 0xb0, 0x01, 0xb0, 0x0d, 0xb0, 0x0d, 0xb0, 0x6d,
+        0xb0, 0x6d, 0xb0, 0x6d, 0xb0,
+    ],
+];
+
+pub const WIFI_OFF: [u8; 20] = [
+    0x40, 0x10, 0x20, 0x20, 0x10, 0x40, 0x08, 0x80, 0x05, 0x00, 0x02, 0x00, 0x05, 0x00, 0x08, 0x80,
+    0x10, 0x40, 0x20, 0x20,
+];
+
+/// Battery charge, 18x10, from empty to four segments.
+pub const BATTERY: [[u8; 30]; 5] = [
+    [
+        0xff, 0xff, 0x00, 0x80, 0x01, 0x00, 0x80, 0x01, 0x00, 0x80, 0x01, 0xc0, 0x80, 0x01, 0xc0,
+        0x80, 0x01, 0xc0, 0x80, 0x01, 0xc0, 0x80, 0x01, 0x00, 0x80, 0x01, 0x00, 0xff, 0xff, 0x00,
+    ],
+    [
+        0xff, 0xff, 0x00, 0x80, 0x01, 0x00, 0xb0, 0x01, 0x00, 0xb0, 0x01, 0xc0, 0xb0, 0x01, 0xc0,
+        0xb0, 0x01, 0xc0, 0xb0, 0x01, 0xc0, 0xb0, 0x01, 0x00, 0x80, 0x01, 0x00, 0xff, 0xff, 0x00,
+    ],
+    [
+        0xff, 0xff, 0x00, 0x80, 0x01, 0x00, 0xb6, 0x01, 0x00, 0xb6, 0x01, 0xc0, 0xb6, 0x01, 0xc0,
+        0xb6, 0x01, 0xc0, 0xb6, 0x01, 0xc0, 0xb6, 0x01, 0x00, 0x80, 0x01, 0x00, 0xff, 0xff, 0x00,
+    ],
+    [
+        0xff, 0xff, 0x00, 0x80, 0x01, 0x00, 0xb6, 0xc1, 0x00, 0xb6, 0xc1, 0xc0, 0xb6, 0xc1, 0xc0,
+        0xb6, 0xc1, 0xc0, 0xb6, 0xc1, 0xc0, 0xb6, 0xc1, 0x00, 0x80, 0x01, 0x00, 0xff, 0xff, 0x00,
+    ],
+    [
+        0xff, 0xff, 0x00, 0x80, 0x01, 0x00, 0xb6, 0xd9, 0x00, 0xb6, 0xd9, 0xc0, 0xb6, 0xd9, 0xc0,
+        0xb6, 0xd9, 0xc0, 0xb6, 0xd9, 0xc0, 0xb6, 0xd9, 0x00, 0x80, 0x01, 0x00, 0xff, 0xff, 0x00,
+    ],
+];
+
+pub const BATTERY_UNKNOWN: [u8; 30] = [
+    0xff, 0xff, 0x00, 0x80, 0x01, 0x00, 0x83, 0x01, 0x00, 0x84, 0x81, 0xc0, 0x80, 0x81, 0xc0, 0x81,
+    0x01, 0xc0, 0x80, 0x01, 0xc0, 0x81, 0x01, 0x00, 0x80, 0x01, 0x00, 0xff, 0xff, 0x00,
+];
+
+/// Broker connection, 7x7: disconnected, connecting and connected.
+pub const MQTT: [[u8; 7]; 3] = [
+    [0x82, 0x44, 0x28, 0x10, 0x28, 0x44, 0x82],
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x92],
+    [0x40, 0xe0, 0x44, 0x44, 0x44, 0x0e, 0x04],
+];
+
+/// Time sync, 7x7.
+pub const CLOCK: [u8; 7] = [0x38, 0x54, 0x92, 0x9a, 0x82, 0x44, 0x38];
+
+pub const HOURGLASS: [u8; 7] = [0xfe, 0x44, 0x28, 0x10, 0x28, 0x54, 0xfe];
+
+/// Messages waiting to be sent, 7x5.
+pub const ENVELOPE: [u8; 5] = [0xfe, 0xc6, 0xaa, 0x92, 0xfe];
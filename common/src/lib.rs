@@ -0,0 +1,17 @@
+//! Firmware logic that does not touch the ESP-IDF, so it builds and is tested
+//! on the host.
+
+pub mod analysis;
+pub mod display;
+pub mod drivers;
+pub mod images;
+pub mod provisioning;
+pub mod storage;
+pub mod utils;
+
+pub const CLIENT_ID: &str = "EQUIPO-2";
+pub const HOST: &str = "192.168.42.102";
+pub const PORT: &str = "1883";
+
+pub const SSID: &str = "Red";
+pub const PASSWORD: &str = "12345678";
@@ -0,0 +1,200 @@
+use crate::utils::settings::Settings;
+use anyhow::{anyhow, Result};
+
+fn hex(byte: u8) -> Option<u8> {
+    (byte as char).to_digit(16).map(|digit| digit as u8)
+}
+
+/// Decodes an `application/x-www-form-urlencoded` value.
+pub fn decode(value: &str) -> Result<String> {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => decoded.push(b' '),
+            b'%' => {
+                let byte = bytes
+                    .get(i + 1)
+                    .and_then(|&high| hex(high))
+                    .zip(bytes.get(i + 2).and_then(|&low| hex(low)))
+                    .map(|(high, low)| high << 4 | low)
+                    .ok_or_else(|| anyhow!("Invalid percent encoding"))?;
+
+                decoded.push(byte);
+                i += 2;
+            }
+            byte => decoded.push(byte),
+        }
+
+        i += 1;
+    }
+
+    Ok(String::from_utf8(decoded)?)
+}
+
+pub fn escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+/// Parses and validates the submitted portal form.
+pub fn parse(body: &str) -> Result<Settings> {
+    let mut settings = Settings {
+        ssid: String::new(),
+        password: String::new(),
+        host: String::new(),
+        port: 0,
+        device: String::new(),
+    };
+
+    for pair in body.trim().split('&').filter(|pair| !pair.is_empty()) {
+        let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+        let value = decode(value)?;
+
+        match decode(key)?.as_str() {
+            "ssid" => settings.ssid = value,
+            "password" => settings.password = value,
+            "host" => settings.host = value.trim().to_string(),
+            "port" => {
+                settings.port = value
+                    .trim()
+                    .parse()
+                    .map_err(|_| anyhow!("Broker port must be a number"))?
+            }
+            "device" => settings.device = value.trim().to_string(),
+            _ => {}
+        }
+    }
+
+    settings.validate()?;
+    Ok(settings)
+}
+
+pub fn render(settings: &Settings, message: Option<&str>) -> String {
+    let field = |label: &str, name: &str, kind: &str, value: &str| {
+        format!(
+            "<label>{}<input name=\"{}\" type=\"{}\" value=\"{}\"></label>",
+            label,
+            name,
+            kind,
+            escape(value)
+        )
+    };
+
+    format!(
+        "<!DOCTYPE html><html><head><meta charset=\"utf-8\">\
+         <meta name=\"viewport\" content=\"width=device-width\">\
+         <title>MicroTime</title>\
+         <style>body{{font-family:sans-serif;max-width:20em;margin:auto}}\
+         label,input,button{{display:block;width:100%;margin-top:.5em}}</style>\
+         </head><body><h1>MicroTime</h1><p>{}</p>\
+         <form method=\"post\" action=\"/\">{}{}{}{}{}<button>Save</button></form>\
+         </body></html>",
+        escape(message.unwrap_or("")),
+        field("Wi-Fi network", "ssid", "text", &settings.ssid),
+        field("Wi-Fi password", "password", "password", ""),
+        field("Broker host", "host", "text", &settings.host),
+        field("Broker port", "port", "number", &settings.port.to_string()),
+        field("Device id", "device", "text", &settings.device),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BODY: &str =
+        "ssid=Casa+Norte&password=p%40ss%20word&host=+10.0.0.2+&port=1884&device=band-01";
+
+    #[test]
+    fn decodes_form_values() {
+        assert_eq!(decode("a+b%2Fc%C3%B1").unwrap(), "a b/cñ");
+        assert!(decode("100%").is_err());
+        assert!(decode("%zz").is_err());
+        assert!(decode("%ff").is_err());
+    }
+
+    #[test]
+    fn parses_the_submitted_settings() {
+        let settings = parse(BODY).unwrap();
+
+        assert_eq!(
+            settings,
+            Settings {
+                ssid: "Casa Norte".to_string(),
+                password: "p@ss word".to_string(),
+                host: "10.0.0.2".to_string(),
+                port: 1884,
+                device: "band-01".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn ignores_unknown_fields() {
+        let body = format!("{}&submit=&extra=1", BODY);
+
+        assert_eq!(parse(&body).unwrap(), parse(BODY).unwrap());
+    }
+
+    #[test]
+    fn rejects_invalid_settings() {
+        let invalid = [
+            BODY.replace("port=1884", "port=abc"),
+            BODY.replace("port=1884", "port=0"),
+            BODY.replace("ssid=Casa+Norte", "ssid="),
+            BODY.replace("password=p%40ss%20word", "password=short"),
+            BODY.replace("host=+10.0.0.2+", "host=broker%2Flocal"),
+            BODY.replace("device=band-01", "device=band%2B1"),
+            BODY.replace("device=band-01", "device=band%2F%23"),
+        ];
+
+        for body in invalid {
+            assert!(parse(&body).is_err(), "{}", body);
+        }
+    }
+
+    #[test]
+    fn allows_an_open_network() {
+        let body = BODY.replace("password=p%40ss%20word", "password=");
+
+        assert!(parse(&body).unwrap().password.is_empty());
+    }
+
+    #[test]
+    fn escapes_rendered_values() {
+        let settings = Settings {
+            ssid: "<script>\"x\"".to_string(),
+            ..Settings::default()
+        };
+        let page = render(&settings, Some("Saved & done"));
+
+        assert!(page.contains("value=\"&lt;script&gt;&quot;x&quot;\""));
+        assert!(page.contains("Saved &amp; done"));
+        assert!(!page.contains("<script>"));
+    }
+
+    #[test]
+    fn never_renders_the_password() {
+        let settings = Settings {
+            password: "secret-password".to_string(),
+            ..Settings::default()
+        };
+
+        assert!(!render(&settings, None).contains("secret-password"));
+    }
+}
@@ -0,0 +1,49 @@
+use super::{Cursor, Entry, Slots};
+use anyhow::Result;
+
+/// Slots kept in RAM, lost on reboot.
+pub struct Memory {
+    slots: Vec<Option<Entry>>,
+    cursor: Option<Cursor>,
+}
+
+impl Memory {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            slots: vec![None; capacity],
+            cursor: None,
+        }
+    }
+}
+
+impl Slots for Memory {
+    fn load(&mut self, slot: usize) -> Result<Option<Entry>> {
+        Ok(self.slots.get(slot).cloned().flatten())
+    }
+
+    fn store(&mut self, slot: usize, entry: &Entry) -> Result<()> {
+        if slot >= self.slots.len() {
+            self.slots.resize(slot + 1, None);
+        }
+
+        self.slots[slot] = Some(entry.clone());
+        Ok(())
+    }
+
+    fn erase(&mut self, slot: usize) -> Result<()> {
+        if let Some(slot) = self.slots.get_mut(slot) {
+            *slot = None;
+        }
+
+        Ok(())
+    }
+
+    fn load_cursor(&mut self) -> Result<Option<Cursor>> {
+        Ok(self.cursor)
+    }
+
+    fn store_cursor(&mut self, cursor: &Cursor) -> Result<()> {
+        self.cursor = Some(*cursor);
+        Ok(())
+    }
+}
@@ -0,0 +1,417 @@
+use anyhow::Result;
+use log::info;
+use serde::{Deserialize, Serialize};
+
+// Changes between two writes of the cursor, so a crash loses at most the
+// last few pushes instead of every push costing a flash write.
+const CURSOR_EVERY: usize = 16;
+
+pub mod memory;
+
+pub use memory::Memory;
+
+/// A message waiting to be published, already serialized with its original headers.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Entry {
+    pub route: String,
+    pub message: String,
+}
+
+/// What to do with a new entry once the queue is full.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Policy {
+    /// Discard the oldest entry.
+    DropOldest,
+    /// Discard every other entry, keeping the newest and those the ring is
+    /// told to keep, so the backlog still covers the whole offline period at
+    /// half the resolution.
+    Downsample,
+}
+
+pub trait Storage: Send {
+    fn push(&mut self, entry: Entry) -> Result<()>;
+
+    /// Returns up to `count` of the oldest entries without removing them.
+    fn peek(&mut self, count: usize) -> Result<Vec<Entry>>;
+
+    /// Removes up to `count` of the oldest entries.
+    fn pop(&mut self, count: usize) -> Result<()>;
+
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub struct Cursor {
+    pub head: usize,
+    pub len: usize,
+}
+
+/// Fixed size slots addressed by index, where a ring keeps its entries.
+pub trait Slots: Send {
+    fn load(&mut self, slot: usize) -> Result<Option<Entry>>;
+
+    fn store(&mut self, slot: usize, entry: &Entry) -> Result<()>;
+
+    fn erase(&mut self, slot: usize) -> Result<()>;
+
+    fn load_cursor(&mut self) -> Result<Option<Cursor>>;
+
+    fn store_cursor(&mut self, cursor: &Cursor) -> Result<()>;
+}
+
+pub struct Ring<S: Slots> {
+    slots: S,
+    capacity: usize,
+    policy: Policy,
+    cursor: Cursor,
+    keep: fn(&Entry) -> bool,
+    unsaved: usize,
+}
+
+impl<S: Slots> Ring<S> {
+    pub fn new(mut slots: S, capacity: usize, policy: Policy) -> Result<Self> {
+        let capacity = capacity.max(1);
+        let cursor = slots
+            .load_cursor()?
+            .filter(|cursor| cursor.head < capacity && cursor.len <= capacity)
+            .unwrap_or_default();
+
+        if cursor.len > 0 {
+            info!("Restored {} queued messages", cursor.len);
+        }
+
+        Ok(Self {
+            slots,
+            capacity,
+            policy,
+            cursor,
+            keep: |_| false,
+            unsaved: 0,
+        })
+    }
+
+    /// Entries that downsampling never discards.
+    pub fn keep(mut self, keep: fn(&Entry) -> bool) -> Self {
+        self.keep = keep;
+        self
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    fn slot(&self, index: usize) -> usize {
+        (self.cursor.head + index) % self.capacity
+    }
+
+    fn save_cursor(&mut self) -> Result<()> {
+        self.unsaved += 1;
+
+        if self.unsaved >= CURSOR_EVERY || self.cursor.len == 0 {
+            self.slots.store_cursor(&self.cursor)?;
+            self.unsaved = 0;
+        }
+
+        Ok(())
+    }
+
+    fn downsample(&mut self) -> Result<()> {
+        let len = self.cursor.len;
+        let mut kept = 0;
+
+        for index in 0..len {
+            let thinned = (len - 1 - index) % 2 == 1;
+            let entry = if thinned || kept != index {
+                self.slots.load(self.slot(index))?
+            } else {
+                None
+            };
+
+            if thinned && !entry.as_ref().is_some_and(self.keep) {
+                continue;
+            }
+
+            if let Some(entry) = entry.filter(|_| kept != index) {
+                self.slots.store(self.slot(kept), &entry)?;
+            }
+
+            kept += 1;
+        }
+
+        for index in kept..len {
+            self.slots.erase(self.slot(index))?;
+        }
+
+        self.cursor.len = kept;
+        self.slots.store_cursor(&self.cursor)?;
+        self.unsaved = 0;
+
+        // Nothing could be discarded, so make room the plain way.
+        if kept >= self.capacity {
+            self.pop(1)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<S: Slots> Storage for Ring<S> {
+    fn push(&mut self, entry: Entry) -> Result<()> {
+        if self.cursor.len >= self.capacity {
+            match self.policy {
+                Policy::DropOldest => self.pop(1)?,
+                Policy::Downsample => self.downsample()?,
+            }
+        }
+
+        self.slots.store(self.slot(self.cursor.len), &entry)?;
+        self.cursor.len += 1;
+        self.save_cursor()
+    }
+
+    fn peek(&mut self, count: usize) -> Result<Vec<Entry>> {
+        let mut entries = Vec::new();
+
+        while entries.len() < count && entries.len() < self.cursor.len {
+            match self.slots.load(self.slot(entries.len())) {
+                Ok(Some(entry)) => entries.push(entry),
+                // An unreadable entry at the front would block the queue forever.
+                Ok(None) | Err(_) if entries.is_empty() => {
+                    info!("Discarding unreadable queued message");
+                    self.pop(1)?;
+                }
+                _ => break,
+            }
+        }
+
+        Ok(entries)
+    }
+
+    fn pop(&mut self, count: usize) -> Result<()> {
+        let count = count.min(self.cursor.len);
+
+        for index in 0..count {
+            self.slots.erase(self.slot(index))?;
+        }
+
+        self.cursor.head = self.slot(count);
+        self.cursor.len -= count;
+        self.save_cursor()
+    }
+
+    fn len(&self) -> usize {
+        self.cursor.len
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    const REPORT: &str = "database/report";
+    const READING: &str = "database/ds18b20";
+
+    /// Memory slots that outlive the ring, like flash across a reboot, and
+    /// count how often the cursor is written.
+    #[derive(Clone)]
+    struct Persisted {
+        memory: Arc<Mutex<Memory>>,
+        cursor_writes: Arc<Mutex<usize>>,
+    }
+
+    impl Persisted {
+        fn new(capacity: usize) -> Self {
+            Self {
+                memory: Arc::new(Mutex::new(Memory::new(capacity))),
+                cursor_writes: Arc::new(Mutex::new(0)),
+            }
+        }
+
+        fn cursor_writes(&self) -> usize {
+            *self.cursor_writes.lock().unwrap()
+        }
+    }
+
+    impl Slots for Persisted {
+        fn load(&mut self, slot: usize) -> Result<Option<Entry>> {
+            self.memory.lock().unwrap().load(slot)
+        }
+
+        fn store(&mut self, slot: usize, entry: &Entry) -> Result<()> {
+            self.memory.lock().unwrap().store(slot, entry)
+        }
+
+        fn erase(&mut self, slot: usize) -> Result<()> {
+            self.memory.lock().unwrap().erase(slot)
+        }
+
+        fn load_cursor(&mut self) -> Result<Option<Cursor>> {
+            self.memory.lock().unwrap().load_cursor()
+        }
+
+        fn store_cursor(&mut self, cursor: &Cursor) -> Result<()> {
+            *self.cursor_writes.lock().unwrap() += 1;
+            self.memory.lock().unwrap().store_cursor(cursor)
+        }
+    }
+
+    fn entry(route: &str, value: usize) -> Entry {
+        Entry {
+            route: route.to_string(),
+            message: value.to_string(),
+        }
+    }
+
+    fn values(ring: &mut impl Storage) -> Vec<usize> {
+        ring.peek(usize::MAX)
+            .unwrap()
+            .iter()
+            .map(|entry| entry.message.parse().unwrap())
+            .collect()
+    }
+
+    fn is_report(entry: &Entry) -> bool {
+        entry.route == REPORT
+    }
+
+    #[test]
+    fn keeps_entries_in_order_across_the_wrap() {
+        let mut ring = Ring::new(Memory::new(4), 4, Policy::DropOldest).unwrap();
+
+        for value in 0..3 {
+            ring.push(entry(READING, value)).unwrap();
+        }
+
+        ring.pop(2).unwrap();
+
+        for value in 3..6 {
+            ring.push(entry(READING, value)).unwrap();
+        }
+
+        assert_eq!(values(&mut ring), vec![2, 3, 4, 5]);
+        assert_eq!(ring.len(), 4);
+    }
+
+    #[test]
+    fn drops_the_oldest_when_full() {
+        let mut ring = Ring::new(Memory::new(3), 3, Policy::DropOldest).unwrap();
+
+        for value in 0..5 {
+            ring.push(entry(READING, value)).unwrap();
+        }
+
+        assert_eq!(values(&mut ring), vec![2, 3, 4]);
+    }
+
+    #[test]
+    fn downsamples_keeping_the_newest() {
+        let mut ring = Ring::new(Memory::new(8), 8, Policy::Downsample).unwrap();
+
+        for value in 0..9 {
+            ring.push(entry(READING, value)).unwrap();
+        }
+
+        assert_eq!(values(&mut ring), vec![1, 3, 5, 7, 8]);
+    }
+
+    #[test]
+    fn downsampling_keeps_reports() {
+        let mut ring = Ring::new(Memory::new(6), 6, Policy::Downsample)
+            .unwrap()
+            .keep(is_report);
+
+        for value in 0..6 {
+            let route = if [0, 2].contains(&value) {
+                REPORT
+            } else {
+                READING
+            };
+            ring.push(entry(route, value)).unwrap();
+        }
+
+        ring.push(entry(READING, 6)).unwrap();
+
+        let entries = ring.peek(usize::MAX).unwrap();
+        assert_eq!(values(&mut ring), vec![0, 1, 2, 3, 5, 6]);
+        assert_eq!(entries.iter().filter(|entry| is_report(entry)).count(), 2);
+    }
+
+    #[test]
+    fn drops_the_oldest_when_everything_is_kept() {
+        let mut ring = Ring::new(Memory::new(3), 3, Policy::Downsample)
+            .unwrap()
+            .keep(is_report);
+
+        for value in 0..4 {
+            ring.push(entry(REPORT, value)).unwrap();
+        }
+
+        assert_eq!(values(&mut ring), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn batches_cursor_writes() {
+        let slots = Persisted::new(64);
+        let mut ring = Ring::new(slots.clone(), 64, Policy::DropOldest).unwrap();
+
+        for value in 0..CURSOR_EVERY * 2 + 3 {
+            ring.push(entry(READING, value)).unwrap();
+        }
+
+        assert_eq!(slots.cursor_writes(), 2);
+    }
+
+    #[test]
+    fn restores_the_queue_after_a_reboot() {
+        let slots = Persisted::new(64);
+        let mut ring = Ring::new(slots.clone(), 64, Policy::DropOldest).unwrap();
+
+        // The last pushes after a cursor write are the ones a crash loses.
+        for value in 0..CURSOR_EVERY + 3 {
+            ring.push(entry(READING, value)).unwrap();
+        }
+
+        let mut restored = Ring::new(slots, 64, Policy::DropOldest).unwrap();
+        assert_eq!(values(&mut restored), (0..CURSOR_EVERY).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn stores_the_cursor_once_the_queue_empties() {
+        let slots = Persisted::new(8);
+        let mut ring = Ring::new(slots.clone(), 8, Policy::DropOldest).unwrap();
+
+        for value in 0..3 {
+            ring.push(entry(READING, value)).unwrap();
+        }
+
+        ring.pop(3).unwrap();
+
+        let restored = Ring::new(slots, 8, Policy::DropOldest).unwrap();
+        assert!(restored.is_empty());
+    }
+
+    #[test]
+    fn ignores_a_cursor_past_the_capacity() {
+        let mut slots = Memory::new(4);
+        slots.store_cursor(&Cursor { head: 9, len: 2 }).unwrap();
+
+        let ring = Ring::new(slots, 4, Policy::DropOldest).unwrap();
+        assert!(ring.is_empty());
+    }
+
+    #[test]
+    fn discards_an_unreadable_front_entry() {
+        let mut slots = Memory::new(4);
+        slots.store(1, &entry(READING, 1)).unwrap();
+        slots.store_cursor(&Cursor { head: 0, len: 2 }).unwrap();
+
+        let mut ring = Ring::new(slots, 4, Policy::DropOldest).unwrap();
+        assert_eq!(values(&mut ring), vec![1]);
+        assert_eq!(ring.len(), 1);
+    }
+}
@@ -0,0 +1,75 @@
+// Resting voltage of a single LiPo cell against its charge, from full to
+// empty.
+const CURVE: [(u32, u8); 12] = [
+    (4200, 100),
+    (4150, 95),
+    (4110, 90),
+    (4020, 80),
+    (3950, 70),
+    (3870, 60),
+    (3830, 50),
+    (3790, 40),
+    (3750, 30),
+    (3700, 20),
+    (3600, 10),
+    (3300, 0),
+];
+
+/// Estimates the charge of the cell, interpolating between points of the
+/// discharge curve.
+pub fn percentage(millivolts: u32) -> u8 {
+    if millivolts >= CURVE[0].0 {
+        return 100;
+    }
+
+    for pair in CURVE.windows(2) {
+        let (high_mv, high) = pair[0];
+        let (low_mv, low) = pair[1];
+
+        if millivolts >= low_mv {
+            let span = (high - low) as u32;
+            return low + ((millivolts - low_mv) * span / (high_mv - low_mv)) as u8;
+        }
+    }
+
+    0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_the_ends_of_the_curve() {
+        assert_eq!(percentage(4200), 100);
+        assert_eq!(percentage(3300), 0);
+    }
+
+    #[test]
+    fn clamps_outside_the_curve() {
+        // A cell on the charger, and one past its cut-off.
+        assert_eq!(percentage(4350), 100);
+        assert_eq!(percentage(3000), 0);
+        assert_eq!(percentage(0), 0);
+    }
+
+    #[test]
+    fn hits_every_point_of_the_curve() {
+        for (millivolts, charge) in CURVE {
+            assert_eq!(percentage(millivolts), charge, "{} mV", millivolts);
+        }
+    }
+
+    #[test]
+    fn interpolates_between_points() {
+        assert_eq!(percentage(3810), 45);
+        assert_eq!(percentage(3450), 5);
+    }
+
+    #[test]
+    fn never_drops_as_the_voltage_rises() {
+        let charges = (3000..=4300).map(percentage).collect::<Vec<_>>();
+
+        assert!(charges.windows(2).all(|pair| pair[0] <= pair[1]));
+    }
+}
@@ -0,0 +1,224 @@
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+pub const DEFAULT: &str = "default";
+
+const MIN_GAIN: f32 = 0.5;
+const MAX_GAIN: f32 = 2.0;
+const MAX_OFFSET: f32 = 10.0;
+const MIN_REFERENCE_SPAN: f32 = 1.0;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct Reference {
+    pub raw: f32,
+    pub actual: f32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct Calibration {
+    #[serde(default)]
+    pub offset: f32,
+    #[serde(default = "default_gain")]
+    pub gain: f32,
+    #[serde(default)]
+    pub reference: Option<[Reference; 2]>,
+}
+
+fn default_gain() -> f32 {
+    1.0
+}
+
+impl Default for Calibration {
+    fn default() -> Self {
+        Self {
+            offset: 0.0,
+            gain: default_gain(),
+            reference: None,
+        }
+    }
+}
+
+impl Calibration {
+    pub fn validate(&self) -> Result<()> {
+        if !(MIN_GAIN..=MAX_GAIN).contains(&self.gain) {
+            bail!("Gain {} out of range", self.gain);
+        }
+
+        if self.offset.abs() > MAX_OFFSET {
+            bail!("Offset {} out of range", self.offset);
+        }
+
+        if let Some([low, high]) = self.reference {
+            if (high.raw - low.raw).abs() < MIN_REFERENCE_SPAN {
+                bail!("Reference points are too close");
+            }
+
+            let gain = (high.actual - low.actual) / (high.raw - low.raw);
+            if !(MIN_GAIN..=MAX_GAIN).contains(&gain) {
+                bail!("Reference gain {} out of range", gain);
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn apply(&self, raw: f32) -> f32 {
+        match self.reference {
+            Some([low, high]) => {
+                let gain = (high.actual - low.actual) / (high.raw - low.raw);
+                low.actual + (raw - low.raw) * gain
+            }
+            None => raw * self.gain + self.offset,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct Calibrations(pub HashMap<String, Calibration>);
+
+impl Calibrations {
+    pub fn get(&self, rom: &str) -> Calibration {
+        self.0
+            .get(rom)
+            .or_else(|| self.0.get(DEFAULT))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    pub fn set(&mut self, rom: Option<&str>, calibration: Calibration) {
+        self.0
+            .insert(rom.unwrap_or(DEFAULT).to_string(), calibration);
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Command {
+    #[serde(default)]
+    pub rom: Option<String>,
+    #[serde(flatten)]
+    pub calibration: Calibration,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ROM: &str = "28012a1700000042";
+
+    fn close(a: f32, b: f32) -> bool {
+        (a - b).abs() < 1e-4
+    }
+
+    fn reference(low: (f32, f32), high: (f32, f32)) -> Option<[Reference; 2]> {
+        Some([
+            Reference {
+                raw: low.0,
+                actual: low.1,
+            },
+            Reference {
+                raw: high.0,
+                actual: high.1,
+            },
+        ])
+    }
+
+    #[test]
+    fn leaves_readings_alone_by_default() {
+        assert!(close(Calibration::default().apply(33.2), 33.2));
+    }
+
+    #[test]
+    fn applies_gain_then_offset() {
+        let calibration = Calibration {
+            offset: 1.5,
+            gain: 1.1,
+            reference: None,
+        };
+
+        assert!(close(calibration.apply(30.0), 34.5));
+    }
+
+    #[test]
+    fn maps_between_two_reference_points() {
+        let calibration = Calibration {
+            reference: reference((30.0, 35.0), (34.0, 39.0)),
+            ..Calibration::default()
+        };
+
+        assert!(close(calibration.apply(30.0), 35.0));
+        assert!(close(calibration.apply(32.0), 37.0));
+        assert!(close(calibration.apply(34.0), 39.0));
+    }
+
+    #[test]
+    fn rejects_bad_calibrations() {
+        let bad = [
+            Calibration {
+                gain: 3.0,
+                ..Calibration::default()
+            },
+            Calibration {
+                gain: 0.0,
+                ..Calibration::default()
+            },
+            Calibration {
+                offset: -12.0,
+                ..Calibration::default()
+            },
+            // Points too close to derive a gain from.
+            Calibration {
+                reference: reference((30.0, 35.0), (30.5, 36.0)),
+                ..Calibration::default()
+            },
+            Calibration {
+                reference: reference((30.0, 35.0), (34.0, 25.0)),
+                ..Calibration::default()
+            },
+        ];
+
+        for calibration in bad {
+            assert!(calibration.validate().is_err(), "{:?}", calibration);
+        }
+
+        assert!(Calibration::default().validate().is_ok());
+    }
+
+    #[test]
+    fn falls_back_to_the_default_calibration() {
+        let mut calibrations = Calibrations::default();
+        assert_eq!(calibrations.get(ROM), Calibration::default());
+
+        let shared = Calibration {
+            offset: 1.0,
+            ..Calibration::default()
+        };
+        let own = Calibration {
+            offset: 2.0,
+            ..Calibration::default()
+        };
+
+        calibrations.set(None, shared);
+        assert_eq!(calibrations.get(ROM), shared);
+
+        calibrations.set(Some(ROM), own);
+        assert_eq!(calibrations.get(ROM), own);
+        assert_eq!(calibrations.get("28012a1700000043"), shared);
+    }
+
+    #[test]
+    fn parses_a_command_with_defaults() {
+        let command =
+            serde_json::from_str::<Command>(&format!(r#"{{"rom": "{}", "offset": 0.4}}"#, ROM))
+                .unwrap();
+
+        assert_eq!(command.rom.as_deref(), Some(ROM));
+        assert_eq!(
+            command.calibration,
+            Calibration {
+                offset: 0.4,
+                ..Calibration::default()
+            }
+        );
+    }
+}
@@ -0,0 +1,103 @@
+use std::time::{Duration, Instant};
+
+const WINDOW: Duration = Duration::from_secs(60);
+
+/// Counts errors within the current minute.
+pub struct Check {
+    max_errors_per_minute: u32,
+    count: u32,
+    since: Instant,
+}
+
+impl Check {
+    pub fn new(max_errors_per_minute: u32) -> Self {
+        Self::new_at(max_errors_per_minute, Instant::now())
+    }
+
+    pub fn new_at(max_errors_per_minute: u32, now: Instant) -> Self {
+        Self {
+            max_errors_per_minute,
+            count: 0,
+            since: now,
+        }
+    }
+
+    // Starts a new window once the current one has passed.
+    fn roll(&mut self, now: Instant) {
+        if now.saturating_duration_since(self.since) >= WINDOW {
+            self.count = 0;
+            self.since = now;
+        }
+    }
+
+    pub fn error(&mut self) {
+        self.error_at(Instant::now())
+    }
+
+    pub fn error_at(&mut self, now: Instant) {
+        self.roll(now);
+        self.count += 1;
+    }
+
+    /// True once this minute's errors reach the limit.
+    pub fn is_limit(&mut self) -> bool {
+        self.is_limit_at(Instant::now())
+    }
+
+    pub fn is_limit_at(&mut self, now: Instant) -> bool {
+        self.roll(now);
+        self.count >= self.max_errors_per_minute
+    }
+
+    pub fn reset(&mut self) {
+        self.count = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stays_under_the_limit() {
+        let start = Instant::now();
+        let mut check = Check::new_at(3, start);
+        assert!(!check.is_limit_at(start));
+
+        check.error_at(start);
+        check.error_at(start);
+        assert!(!check.is_limit_at(start));
+    }
+
+    #[test]
+    fn trips_at_the_limit() {
+        let start = Instant::now();
+        let mut check = Check::new_at(3, start);
+
+        for _ in 0..3 {
+            check.error_at(start);
+        }
+
+        assert!(check.is_limit_at(start + Duration::from_secs(30)));
+
+        check.reset();
+        assert!(!check.is_limit_at(start + Duration::from_secs(30)));
+    }
+
+    #[test]
+    fn forgets_errors_older_than_the_window() {
+        let start = Instant::now();
+        let mut check = Check::new_at(3, start);
+
+        for _ in 0..3 {
+            check.error_at(start);
+        }
+
+        assert!(!check.is_limit_at(start + WINDOW));
+
+        // Counted afresh in the new window.
+        check.error_at(start + WINDOW);
+        check.error_at(start + WINDOW);
+        assert!(!check.is_limit_at(start + WINDOW));
+    }
+}
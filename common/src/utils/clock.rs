@@ -0,0 +1,203 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+// An SNTP sync older than this no longer outranks a server offer.
+const SNTP_STALE_MS: u64 = 6 * 60 * 60 * 1000;
+
+/// Where the current wall time came from, from least to most trusted.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum Source {
+    None,
+    Server,
+    Sntp,
+}
+
+/// The time the server publishes for devices that cannot reach an NTP
+/// server.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+pub struct Offer {
+    pub unix_ms: i64,
+}
+
+/// When a reading was taken. Before the clock is synced the timestamp is
+/// only the uptime, and `uptime` plus `boot` are enough to fix it later.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Stamp {
+    pub timestamp: i64,
+    pub uptime: u64,
+    pub boot: u32,
+    pub synced: bool,
+}
+
+/// Wall time as an offset over the monotonic uptime, so readings keep a
+/// consistent order whatever the wall clock does.
+pub struct Clock {
+    boot: u32,
+    offset_ms: Option<i64>,
+    source: Source,
+    synced_at: u64,
+}
+
+impl Clock {
+    pub fn new(boot: u32) -> Self {
+        Self {
+            boot,
+            offset_ms: None,
+            source: Source::None,
+            synced_at: 0,
+        }
+    }
+
+    pub fn source(&self) -> Source {
+        self.source
+    }
+
+    pub fn is_synced(&self) -> bool {
+        self.offset_ms.is_some()
+    }
+
+    /// Takes `unix_ms` as the wall time at `uptime_ms`, unless a more trusted
+    /// source synced recently. Returns whether it was applied.
+    pub fn sync(&mut self, source: Source, unix_ms: i64, uptime_ms: u64) -> bool {
+        let stale = uptime_ms.saturating_sub(self.synced_at) > SNTP_STALE_MS;
+
+        if source < self.source && !stale {
+            return false;
+        }
+
+        self.offset_ms = Some(unix_ms - uptime_ms as i64);
+        self.source = source;
+        self.synced_at = uptime_ms;
+        true
+    }
+
+    pub fn now(&self, uptime_ms: u64) -> Option<i64> {
+        self.offset_ms.map(|offset| offset + uptime_ms as i64)
+    }
+
+    pub fn stamp(&self, uptime_ms: u64) -> Stamp {
+        Stamp {
+            timestamp: self.now(uptime_ms).unwrap_or(uptime_ms as i64) / 1000,
+            uptime: uptime_ms,
+            boot: self.boot,
+            synced: self.is_synced(),
+        }
+    }
+
+    /// Gives an unsynced stamp from this boot its wall time, once known.
+    pub fn resolve(&self, stamp: Stamp) -> Stamp {
+        if stamp.synced || stamp.boot != self.boot || !self.is_synced() {
+            return stamp;
+        }
+
+        self.stamp(stamp.uptime)
+    }
+
+    /// Resolves the headers of a serialized message, returning `None` when
+    /// there is nothing to correct.
+    pub fn correct(&self, message: &str) -> Option<String> {
+        let mut value = serde_json::from_str::<Value>(message).ok()?;
+        let headers = value.get_mut("headers")?;
+        let stamp = serde_json::from_value::<Stamp>(headers.clone()).ok()?;
+        let resolved = self.resolve(stamp);
+
+        if resolved == stamp {
+            return None;
+        }
+
+        *headers = serde_json::to_value(resolved).ok()?;
+        Some(value.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BOOT: u32 = 42;
+    const UNIX_MS: i64 = 1_700_000_000_000;
+
+    #[test]
+    fn stamps_the_uptime_until_synced() {
+        let clock = Clock::new(BOOT);
+
+        assert_eq!(
+            clock.stamp(5_500),
+            Stamp {
+                timestamp: 5,
+                uptime: 5_500,
+                boot: BOOT,
+                synced: false,
+            }
+        );
+    }
+
+    #[test]
+    fn keeps_time_from_the_uptime_once_synced() {
+        let mut clock = Clock::new(BOOT);
+        assert!(clock.sync(Source::Server, UNIX_MS, 10_000));
+
+        assert_eq!(clock.now(70_000), Some(UNIX_MS + 60_000));
+        assert_eq!(clock.stamp(70_000).timestamp, (UNIX_MS + 60_000) / 1000);
+        assert!(clock.stamp(70_000).synced);
+    }
+
+    #[test]
+    fn prefers_a_recent_sntp_sync() {
+        let mut clock = Clock::new(BOOT);
+        clock.sync(Source::Sntp, UNIX_MS, 0);
+
+        assert!(!clock.sync(Source::Server, UNIX_MS + 5_000, 1_000));
+        assert_eq!(clock.source(), Source::Sntp);
+        assert_eq!(clock.now(1_000), Some(UNIX_MS + 1_000));
+
+        let stale = SNTP_STALE_MS + 1;
+        assert!(clock.sync(Source::Server, UNIX_MS, stale));
+        assert_eq!(clock.source(), Source::Server);
+    }
+
+    #[test]
+    fn resolves_stamps_from_this_boot() {
+        let mut clock = Clock::new(BOOT);
+        let early = clock.stamp(2_000);
+
+        assert_eq!(clock.resolve(early), early);
+
+        clock.sync(Source::Server, UNIX_MS, 10_000);
+        let resolved = clock.resolve(early);
+
+        assert_eq!(resolved.timestamp, (UNIX_MS - 8_000) / 1000);
+        assert_eq!(resolved.uptime, 2_000);
+        assert!(resolved.synced);
+    }
+
+    #[test]
+    fn leaves_stamps_from_another_boot() {
+        let before = Clock::new(BOOT - 1).stamp(2_000);
+        let mut clock = Clock::new(BOOT);
+        clock.sync(Source::Server, UNIX_MS, 10_000);
+
+        assert_eq!(clock.resolve(before), before);
+    }
+
+    #[test]
+    fn corrects_serialized_headers() {
+        let mut clock = Clock::new(BOOT);
+        let message = serde_json::json!({
+            "headers": clock.stamp(2_000),
+            "payload": { "temperature": 36.5 },
+        })
+        .to_string();
+
+        assert_eq!(clock.correct(&message), None);
+
+        clock.sync(Source::Server, UNIX_MS, 10_000);
+        let corrected = serde_json::from_str::<Value>(&clock.correct(&message).unwrap()).unwrap();
+
+        assert_eq!(corrected["headers"]["synced"], true);
+        assert_eq!(corrected["headers"]["timestamp"], (UNIX_MS - 8_000) / 1000);
+        assert_eq!(corrected["payload"]["temperature"], 36.5);
+        assert_eq!(clock.correct(&corrected.to_string()), None);
+    }
+}
@@ -0,0 +1,325 @@
+use crate::analysis::{activity::Sex, hrv};
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{ops::RangeInclusive, time::Duration};
+
+pub const CONFIG: &str = "config";
+
+const SAMPLE_MS: RangeInclusive<u32> = 10..=60_000;
+const PERIOD_MS: RangeInclusive<u32> = 10..=3_600_000;
+const ADC_MAX: u32 = (1 << 18) - 1;
+// The beat gate never rejects an interval the HRV window would keep.
+const BEAT_INTERVAL_MS: RangeInclusive<u32> = hrv::MIN_INTERVAL_MS..=hrv::MAX_INTERVAL_MS;
+// The pulse sensor samples at 400 Hz and averages 4 samples per FIFO entry,
+// so its 32 entries fill in 320 ms and have to be drained sooner.
+const MAX3010X_SAMPLE_MS: u32 = 300;
+const FALL_COUNTDOWN_S: RangeInclusive<u32> = 5..=120;
+const WEIGHT: RangeInclusive<f32> = 20.0..=300.0;
+const AGE: RangeInclusive<u32> = 5..=120;
+// An average adult until the profile is set through the config topic.
+const DEFAULT_WEIGHT: f32 = 70.0;
+const DEFAULT_AGE: u32 = 30;
+
+/// How often a sensor is sampled and how often its readings are published.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct Sampling {
+    pub sample_ms: u32,
+    pub live_ms: u32,
+    pub persist_ms: u32,
+}
+
+impl Sampling {
+    pub fn tick(&self) -> Duration {
+        Duration::from_millis(self.sample_ms as u64)
+    }
+
+    pub fn live_every(&self) -> u32 {
+        every(self.live_ms, self.sample_ms)
+    }
+
+    pub fn persist_every(&self) -> u32 {
+        every(self.persist_ms, self.sample_ms)
+    }
+
+    fn validate(&self, name: &str) -> Result<()> {
+        if !SAMPLE_MS.contains(&self.sample_ms) {
+            bail!("{}: sample period out of range", name);
+        }
+
+        for period in [self.live_ms, self.persist_ms] {
+            if !PERIOD_MS.contains(&period) || period < self.sample_ms {
+                bail!(
+                    "{}: publish period must not be shorter than the sample period",
+                    name
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Number of samples between two runs of a sink publishing every `period_ms`.
+pub fn every(period_ms: u32, sample_ms: u32) -> u32 {
+    (period_ms / sample_ms.max(1)).max(1)
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(default)]
+pub struct Max3010x {
+    pub sampling: Sampling,
+    pub hrv_ms: u32,
+    pub peak_threshold: u32,
+    pub min_ir: u32,
+    pub min_red: u32,
+    pub variability: f32,
+    pub min_interval_ms: u32,
+}
+
+impl Default for Max3010x {
+    fn default() -> Self {
+        Self {
+            sampling: Sampling {
+                sample_ms: 100,
+                live_ms: 100,
+                persist_ms: 2_000,
+            },
+            hrv_ms: 30_000,
+            peak_threshold: 50_000,
+            min_ir: 20_000,
+            min_red: 20_000,
+            variability: 1_000.0,
+            min_interval_ms: hrv::MIN_INTERVAL_MS,
+        }
+    }
+}
+
+impl Max3010x {
+    pub fn hrv_every(&self) -> u32 {
+        every(self.hrv_ms, self.sampling.sample_ms)
+    }
+}
+
+/// The motion sensor runs at a fixed rate that the fall and activity
+/// detectors are tuned for, only its publish periods are configurable.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(default)]
+pub struct Mpu6050 {
+    pub live_ms: u32,
+    pub persist_ms: u32,
+    pub fall_countdown_s: u32,
+}
+
+impl Default for Mpu6050 {
+    fn default() -> Self {
+        Self {
+            live_ms: 5_000,
+            persist_ms: 3_000,
+            fall_countdown_s: 15,
+        }
+    }
+}
+
+impl Mpu6050 {
+    pub fn fall_countdown(&self) -> Duration {
+        Duration::from_secs(self.fall_countdown_s as u64)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(default)]
+pub struct Wearer {
+    pub weight: f32,
+    pub age: u32,
+    pub sex: Option<Sex>,
+}
+
+impl Default for Wearer {
+    fn default() -> Self {
+        Self {
+            weight: DEFAULT_WEIGHT,
+            age: DEFAULT_AGE,
+            sex: None,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(default)]
+pub struct Config {
+    pub ds18b20: Sampling,
+    pub max3010x: Max3010x,
+    pub mpu6050: Mpu6050,
+    pub wearer: Wearer,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            ds18b20: Sampling {
+                sample_ms: 1_000,
+                live_ms: 2_000,
+                persist_ms: 5_000,
+            },
+            max3010x: Max3010x::default(),
+            mpu6050: Mpu6050::default(),
+            wearer: Wearer::default(),
+        }
+    }
+}
+
+impl Config {
+    pub fn validate(&self) -> Result<()> {
+        self.ds18b20.validate("ds18b20")?;
+        self.max3010x.sampling.validate("max3010x")?;
+
+        let pulse = &self.max3010x;
+
+        if pulse.sampling.sample_ms > MAX3010X_SAMPLE_MS {
+            bail!(
+                "max3010x: sample period must not exceed {} ms",
+                MAX3010X_SAMPLE_MS
+            );
+        }
+
+        if !PERIOD_MS.contains(&pulse.hrv_ms) || pulse.hrv_ms < pulse.sampling.sample_ms {
+            bail!("max3010x: hrv period must not be shorter than the sample period");
+        }
+
+        if [pulse.peak_threshold, pulse.min_ir, pulse.min_red]
+            .iter()
+            .any(|value| *value > ADC_MAX)
+        {
+            bail!("max3010x: thresholds must be below {}", ADC_MAX);
+        }
+
+        if pulse.variability.is_nan() || pulse.variability <= 0.0 {
+            bail!("max3010x: variability must be positive");
+        }
+
+        if !BEAT_INTERVAL_MS.contains(&pulse.min_interval_ms) {
+            bail!("max3010x: minimum beat interval out of range");
+        }
+
+        for period in [self.mpu6050.live_ms, self.mpu6050.persist_ms] {
+            if !PERIOD_MS.contains(&period) {
+                bail!("mpu6050: publish period out of range");
+            }
+        }
+
+        if !FALL_COUNTDOWN_S.contains(&self.mpu6050.fall_countdown_s) {
+            bail!("mpu6050: fall countdown out of range");
+        }
+
+        if !WEIGHT.contains(&self.wearer.weight) || !AGE.contains(&self.wearer.age) {
+            bail!("wearer: weight or age out of range");
+        }
+
+        Ok(())
+    }
+
+    /// Applies a partial JSON update, keeping every field it does not mention.
+    pub fn patch(&self, data: &str) -> Result<Config> {
+        let mut value = serde_json::to_value(self)?;
+        merge(&mut value, serde_json::from_str(data)?);
+
+        let config = serde_json::from_value::<Config>(value)?;
+        config.validate()?;
+        Ok(config)
+    }
+}
+
+fn merge(base: &mut Value, patch: Value) {
+    match (base, patch) {
+        (Value::Object(base), Value::Object(patch)) => {
+            for (key, value) in patch {
+                merge(base.entry(key).or_insert(Value::Null), value);
+            }
+        }
+        (base, patch) => *base = patch,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn defaults_are_valid() {
+        assert!(Config::default().validate().is_ok());
+    }
+
+    #[test]
+    fn patches_only_the_fields_given() {
+        let config = Config::default();
+        let patched = config
+            .patch(r#"{"max3010x": {"sampling": {"live_ms": 500}}, "wearer": {"age": 64}}"#)
+            .unwrap();
+
+        assert_eq!(patched.max3010x.sampling.live_ms, 500);
+        assert_eq!(patched.wearer.age, 64);
+
+        assert_eq!(
+            patched.max3010x.sampling.sample_ms,
+            config.max3010x.sampling.sample_ms
+        );
+        assert_eq!(patched.max3010x.hrv_ms, config.max3010x.hrv_ms);
+        assert_eq!(patched.wearer.weight, config.wearer.weight);
+        assert_eq!(patched.ds18b20, config.ds18b20);
+        assert_eq!(patched.mpu6050, config.mpu6050);
+    }
+
+    #[test]
+    fn rejects_a_patch_with_any_value_out_of_range() {
+        let config = Config::default();
+
+        // The valid part of the patch must not be applied either.
+        let patches = [
+            r#"{"ds18b20": {"sample_ms": 5}}"#,
+            r#"{"ds18b20": {"live_ms": 500}}"#,
+            r#"{"max3010x": {"sampling": {"sample_ms": 400}}}"#,
+            r#"{"max3010x": {"peak_threshold": 300000}}"#,
+            r#"{"max3010x": {"variability": 0.0}}"#,
+            r#"{"max3010x": {"min_interval_ms": 100}}"#,
+            r#"{"mpu6050": {"fall_countdown_s": 1}}"#,
+            r#"{"wearer": {"age": 30, "weight": 5.0}}"#,
+        ];
+
+        for patch in patches {
+            assert!(config.patch(patch).is_err(), "{}", patch);
+        }
+
+        assert_eq!(config, Config::default());
+    }
+
+    #[test]
+    fn rejects_values_of_the_wrong_type() {
+        assert!(Config::default()
+            .patch(r#"{"wearer": {"age": "old"}}"#)
+            .is_err());
+        assert!(Config::default().patch("not json").is_err());
+    }
+
+    #[test]
+    fn fills_missing_fields_with_defaults() {
+        let config = serde_json::from_str::<Config>(r#"{"mpu6050": {"live_ms": 1000}}"#).unwrap();
+
+        assert_eq!(config.mpu6050.live_ms, 1000);
+        assert_eq!(
+            config.mpu6050.fall_countdown_s,
+            Mpu6050::default().fall_countdown_s
+        );
+        assert_eq!(config.max3010x, Max3010x::default());
+        assert_eq!(config.wearer, Wearer::default());
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn counts_samples_between_publishes() {
+        assert_eq!(every(2_000, 100), 20);
+        assert_eq!(every(100, 100), 1);
+        assert_eq!(every(50, 100), 1);
+        assert_eq!(every(1_000, 0), 1_000);
+    }
+}
@@ -0,0 +1,282 @@
+use std::time::Duration;
+
+const MAX_ATTEMPT: u32 = 16;
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Exponential backoff with equal jitter: half of the delay is fixed and the
+/// other half random, so devices that dropped together do not retry together.
+pub struct Backoff {
+    min: Duration,
+    max: Duration,
+    attempt: u32,
+    state: u32,
+}
+
+impl Backoff {
+    pub fn new(min: Duration, max: Duration, seed: u32) -> Self {
+        Self {
+            min,
+            max,
+            attempt: 0,
+            state: seed | 1,
+        }
+    }
+
+    pub fn next_delay(&mut self) -> Duration {
+        let delay = self
+            .min
+            .saturating_mul(1 << self.attempt.min(MAX_ATTEMPT))
+            .min(self.max);
+        self.attempt = (self.attempt + 1).min(MAX_ATTEMPT);
+
+        let half = delay / 2;
+        half + half.mul_f32(self.random())
+    }
+
+    pub fn reset(&mut self) {
+        self.attempt = 0;
+    }
+
+    // xorshift32, returns a value in [0, 1).
+    fn random(&mut self) -> f32 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 17;
+        self.state ^= self.state << 5;
+        (self.state >> 8) as f32 / (1 << 24) as f32
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Status {
+    Disconnected,
+    Connecting,
+    Connected,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Event {
+    NetworkUp,
+    NetworkDown,
+    Connected { session_present: bool },
+    Disconnected,
+    Poll,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Action {
+    /// Build a new client and start connecting to the broker.
+    Connect,
+    /// Drop the current client.
+    Disconnect,
+    /// The broker kept no session, subscriptions must be sent again.
+    Resubscribe,
+    /// Publish the online status that the last will overrides.
+    Announce,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum State {
+    Offline,
+    Waiting { until: Duration },
+    Connecting { since: Duration },
+    Online,
+}
+
+pub struct Connection {
+    state: State,
+    backoff: Backoff,
+}
+
+impl Connection {
+    pub fn new(backoff: Backoff) -> Self {
+        Self {
+            state: State::Offline,
+            backoff,
+        }
+    }
+
+    pub fn status(&self) -> Status {
+        match self.state {
+            State::Offline | State::Waiting { .. } => Status::Disconnected,
+            State::Connecting { .. } => Status::Connecting,
+            State::Online => Status::Connected,
+        }
+    }
+
+    pub fn handle(&mut self, event: Event, now: Duration) -> Vec<Action> {
+        match (self.state, event) {
+            (_, Event::NetworkDown) => {
+                self.state = State::Offline;
+                vec![Action::Disconnect]
+            }
+            (State::Offline, Event::NetworkUp) => self.connect(now),
+            (
+                State::Connecting { .. } | State::Waiting { .. },
+                Event::Connected { session_present },
+            ) => {
+                self.state = State::Online;
+                self.backoff.reset();
+
+                if session_present {
+                    vec![Action::Announce]
+                } else {
+                    vec![Action::Resubscribe, Action::Announce]
+                }
+            }
+            (State::Connecting { .. } | State::Online, Event::Disconnected) => self.retry(now),
+            (State::Waiting { until }, Event::Poll) if now >= until => self.connect(now),
+            (State::Connecting { since }, Event::Poll) if now >= since + CONNECT_TIMEOUT => {
+                self.retry(now)
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    fn connect(&mut self, now: Duration) -> Vec<Action> {
+        self.state = State::Connecting { since: now };
+        vec![Action::Connect]
+    }
+
+    fn retry(&mut self, now: Duration) -> Vec<Action> {
+        let delay = self.backoff.next_delay();
+        self.state = State::Waiting { until: now + delay };
+        vec![Action::Disconnect]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MIN: Duration = Duration::from_secs(1);
+    const MAX: Duration = Duration::from_secs(60);
+
+    fn secs(secs: u64) -> Duration {
+        Duration::from_secs(secs)
+    }
+
+    fn online() -> Connection {
+        let mut connection = Connection::new(Backoff::new(MIN, MAX, 7));
+        connection.handle(Event::NetworkUp, secs(0));
+        connection.handle(
+            Event::Connected {
+                session_present: true,
+            },
+            secs(1),
+        );
+        connection
+    }
+
+    #[test]
+    fn backs_off_with_jitter_up_to_the_maximum() {
+        let mut backoff = Backoff::new(MIN, MAX, 7);
+
+        for attempt in 0..10 {
+            let full = MIN.saturating_mul(1 << attempt).min(MAX);
+            let delay = backoff.next_delay();
+
+            assert!(delay >= full / 2 && delay <= full, "{:?}", delay);
+        }
+
+        backoff.reset();
+        assert!(backoff.next_delay() <= MIN);
+    }
+
+    #[test]
+    fn spreads_devices_with_different_seeds() {
+        let delays = (1..5)
+            .map(|seed| Backoff::new(MIN, MAX, seed).next_delay())
+            .collect::<Vec<_>>();
+
+        assert!(delays.windows(2).any(|pair| pair[0] != pair[1]));
+    }
+
+    #[test]
+    fn connects_once_the_network_is_up() {
+        let mut connection = Connection::new(Backoff::new(MIN, MAX, 7));
+        assert_eq!(connection.status(), Status::Disconnected);
+
+        assert_eq!(
+            connection.handle(Event::NetworkUp, secs(0)),
+            vec![Action::Connect]
+        );
+        assert_eq!(connection.status(), Status::Connecting);
+    }
+
+    #[test]
+    fn resubscribes_without_a_session() {
+        let mut connection = Connection::new(Backoff::new(MIN, MAX, 7));
+        connection.handle(Event::NetworkUp, secs(0));
+
+        let actions = connection.handle(
+            Event::Connected {
+                session_present: false,
+            },
+            secs(1),
+        );
+
+        assert_eq!(actions, vec![Action::Resubscribe, Action::Announce]);
+        assert_eq!(connection.status(), Status::Connected);
+    }
+
+    #[test]
+    fn keeps_the_subscriptions_of_a_session() {
+        let mut connection = Connection::new(Backoff::new(MIN, MAX, 7));
+        connection.handle(Event::NetworkUp, secs(0));
+
+        let actions = connection.handle(
+            Event::Connected {
+                session_present: true,
+            },
+            secs(1),
+        );
+
+        assert_eq!(actions, vec![Action::Announce]);
+    }
+
+    #[test]
+    fn waits_before_reconnecting() {
+        let mut connection = online();
+
+        assert_eq!(
+            connection.handle(Event::Disconnected, secs(10)),
+            vec![Action::Disconnect]
+        );
+        assert_eq!(connection.status(), Status::Disconnected);
+
+        // The first delay is at most the minimum.
+        assert!(connection.handle(Event::Poll, secs(10)).is_empty());
+        assert_eq!(
+            connection.handle(Event::Poll, secs(11)),
+            vec![Action::Connect]
+        );
+    }
+
+    #[test]
+    fn gives_up_on_a_hanging_attempt() {
+        let mut connection = Connection::new(Backoff::new(MIN, MAX, 7));
+        connection.handle(Event::NetworkUp, secs(0));
+
+        assert!(connection.handle(Event::Poll, secs(14)).is_empty());
+        assert_eq!(
+            connection.handle(Event::Poll, CONNECT_TIMEOUT),
+            vec![Action::Disconnect]
+        );
+        assert_eq!(connection.status(), Status::Disconnected);
+    }
+
+    #[test]
+    fn stops_while_the_network_is_down() {
+        let mut connection = online();
+
+        assert_eq!(
+            connection.handle(Event::NetworkDown, secs(5)),
+            vec![Action::Disconnect]
+        );
+        assert!(connection.handle(Event::Poll, secs(500)).is_empty());
+        assert_eq!(
+            connection.handle(Event::NetworkUp, secs(501)),
+            vec![Action::Connect]
+        );
+    }
+}
@@ -0,0 +1,161 @@
+use std::{
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum State {
+    Idle,
+    Running { reason: String, remaining: Duration },
+    Expired { reason: String },
+}
+
+pub struct Countdown {
+    current: Mutex<Option<(String, Instant)>>,
+}
+
+impl Countdown {
+    pub fn new() -> Self {
+        Self {
+            current: Mutex::new(None),
+        }
+    }
+
+    pub fn start(&self, reason: &str, duration: Duration) -> bool {
+        self.start_at(reason, duration, Instant::now())
+    }
+
+    pub fn start_at(&self, reason: &str, duration: Duration, now: Instant) -> bool {
+        if let Ok(mut current) = self.current.lock() {
+            if current.is_none() {
+                *current = Some((reason.to_string(), now + duration));
+                return true;
+            }
+        }
+
+        false
+    }
+
+    pub fn cancel(&self) -> bool {
+        if let Ok(mut current) = self.current.lock() {
+            return current.take().is_some();
+        }
+
+        false
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.current.lock().is_ok_and(|current| current.is_some())
+    }
+
+    /// The running countdown and the time left on it, without expiring it.
+    pub fn remaining(&self) -> Option<(String, Duration)> {
+        self.remaining_at(Instant::now())
+    }
+
+    pub fn remaining_at(&self, now: Instant) -> Option<(String, Duration)> {
+        let current = self.current.lock().ok()?;
+        let (reason, deadline) = current.as_ref()?;
+        Some((reason.clone(), deadline.saturating_duration_since(now)))
+    }
+
+    pub fn poll(&self) -> State {
+        self.poll_at(Instant::now())
+    }
+
+    pub fn poll_at(&self, now: Instant) -> State {
+        if let Ok(mut current) = self.current.lock() {
+            if let Some((reason, deadline)) = current.as_ref() {
+                if now < *deadline {
+                    return State::Running {
+                        reason: reason.clone(),
+                        remaining: *deadline - now,
+                    };
+                }
+
+                let reason = reason.clone();
+                *current = None;
+                return State::Expired { reason };
+            }
+        }
+
+        State::Idle
+    }
+}
+
+impl Default for Countdown {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DURATION: Duration = Duration::from_secs(15);
+
+    #[test]
+    fn counts_down_while_running() {
+        let countdown = Countdown::new();
+        let start = Instant::now();
+        assert_eq!(countdown.poll_at(start), State::Idle);
+
+        assert!(countdown.start_at("fall", DURATION, start));
+        assert!(countdown.is_running());
+
+        let now = start + Duration::from_secs(5);
+        assert_eq!(
+            countdown.poll_at(now),
+            State::Running {
+                reason: "fall".to_string(),
+                remaining: Duration::from_secs(10),
+            }
+        );
+        assert_eq!(
+            countdown.remaining_at(now),
+            Some(("fall".to_string(), Duration::from_secs(10)))
+        );
+    }
+
+    #[test]
+    fn keeps_the_first_of_two_countdowns() {
+        let countdown = Countdown::new();
+        let start = Instant::now();
+
+        assert!(countdown.start_at("fall", DURATION, start));
+        assert!(!countdown.start_at("sos", DURATION, start));
+        assert_eq!(countdown.remaining_at(start).unwrap().0, "fall");
+    }
+
+    #[test]
+    fn cancels_before_expiring() {
+        let countdown = Countdown::new();
+        let start = Instant::now();
+        countdown.start_at("fall", DURATION, start);
+
+        assert!(countdown.cancel());
+        assert!(!countdown.cancel());
+        assert!(!countdown.is_running());
+        assert_eq!(countdown.poll_at(start + DURATION), State::Idle);
+    }
+
+    #[test]
+    fn expires_once() {
+        let countdown = Countdown::new();
+        let start = Instant::now();
+        countdown.start_at("fall", DURATION, start);
+        countdown.cancel();
+        countdown.start_at("sos", DURATION, start);
+
+        let deadline = start + DURATION;
+        assert_eq!(
+            countdown.poll_at(deadline),
+            State::Expired {
+                reason: "sos".to_string()
+            }
+        );
+        assert_eq!(countdown.poll_at(deadline), State::Idle);
+        assert!(!countdown.cancel());
+    }
+}
@@ -0,0 +1,239 @@
+// How long the level must stay put before an edge counts.
+const DEBOUNCE_MS: u64 = 30;
+// How long after a short press a second one still makes a double press.
+const DOUBLE_GAP_MS: u64 = 300;
+const LONG_MS: u64 = 1000;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Gesture {
+    /// Pressed briefly once.
+    Short,
+    /// Two brief presses in quick succession.
+    Double,
+    /// Still pressed after a second. Fires without waiting for the release,
+    /// which is then ignored.
+    Long,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum State {
+    Idle,
+    Down { since: u64, presses: u8 },
+    Up { since: u64 },
+    // Past `Long`, waiting for the release.
+    Long,
+}
+
+/// Turns the raw button level into gestures. It is fed the level with the
+/// uptime whenever the pin changes, and periodically while it is busy so
+/// the timeouts fire.
+pub struct Detector {
+    raw: bool,
+    raw_since: u64,
+    level: bool,
+    state: State,
+}
+
+impl Detector {
+    pub fn new() -> Self {
+        Self {
+            raw: false,
+            raw_since: 0,
+            level: false,
+            state: State::Idle,
+        }
+    }
+
+    /// Whether nothing can happen until the level changes again.
+    pub fn is_idle(&self) -> bool {
+        self.state == State::Idle && self.raw == self.level
+    }
+
+    pub fn update(&mut self, pressed: bool, now_ms: u64) -> Option<Gesture> {
+        if pressed != self.raw {
+            self.raw = pressed;
+            self.raw_since = now_ms;
+        }
+
+        if self.raw != self.level && now_ms.saturating_sub(self.raw_since) >= DEBOUNCE_MS {
+            self.level = self.raw;
+
+            // Timed from when the level first changed, not when it settled.
+            if let Some(gesture) = self.edge(self.level, self.raw_since) {
+                return Some(gesture);
+            }
+        }
+
+        self.expire(now_ms)
+    }
+
+    fn edge(&mut self, pressed: bool, at: u64) -> Option<Gesture> {
+        match (self.state, pressed) {
+            (State::Idle, true) => {
+                self.state = State::Down {
+                    since: at,
+                    presses: 1,
+                };
+                None
+            }
+            (State::Up { .. }, true) => {
+                self.state = State::Down {
+                    since: at,
+                    presses: 2,
+                };
+                None
+            }
+            (State::Down { presses, .. }, false) => {
+                if presses > 1 {
+                    self.state = State::Idle;
+                    Some(Gesture::Double)
+                } else {
+                    self.state = State::Up { since: at };
+                    None
+                }
+            }
+            (State::Long, false) => {
+                self.state = State::Idle;
+                None
+            }
+            _ => None,
+        }
+    }
+
+    fn expire(&mut self, now_ms: u64) -> Option<Gesture> {
+        match self.state {
+            State::Down { since, .. } if now_ms.saturating_sub(since) >= LONG_MS => {
+                self.state = State::Long;
+                Some(Gesture::Long)
+            }
+            State::Up { since } if now_ms.saturating_sub(since) >= DOUBLE_GAP_MS => {
+                self.state = State::Idle;
+                Some(Gesture::Short)
+            }
+            _ => None,
+        }
+    }
+}
+
+impl Default for Detector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const POLL_MS: usize = 5;
+
+    /// Plays the level changes at `edges` into a detector polled every few
+    /// milliseconds until `until`, returning the gestures with their times.
+    fn run(edges: &[(u64, bool)], until: u64) -> Vec<(u64, Gesture)> {
+        let mut detector = Detector::new();
+        let mut gestures = Vec::new();
+        let mut edges = edges.iter().peekable();
+        let mut pressed = false;
+
+        for now in (0..=until).step_by(POLL_MS) {
+            while let Some((_, level)) = edges.next_if(|(at, _)| *at <= now) {
+                pressed = *level;
+            }
+
+            if let Some(gesture) = detector.update(pressed, now) {
+                gestures.push((now, gesture));
+            }
+        }
+
+        gestures
+    }
+
+    fn gestures(edges: &[(u64, bool)], until: u64) -> Vec<Gesture> {
+        run(edges, until)
+            .into_iter()
+            .map(|(_, gesture)| gesture)
+            .collect()
+    }
+
+    #[test]
+    fn recognises_a_short_press_once_no_second_follows() {
+        let fired = run(&[(100, true), (250, false)], 2000);
+
+        assert_eq!(fired.len(), 1);
+        assert_eq!(fired[0].1, Gesture::Short);
+        assert!((550..=600).contains(&fired[0].0), "{:?}", fired);
+    }
+
+    #[test]
+    fn debounces_a_bouncing_contact() {
+        let edges = [
+            (100, true),
+            (105, false),
+            (110, true),
+            (115, false),
+            (120, true),
+            (300, false),
+            (305, true),
+            (310, false),
+        ];
+
+        assert_eq!(gestures(&edges, 2000), vec![Gesture::Short]);
+    }
+
+    #[test]
+    fn ignores_a_glitch() {
+        assert!(run(&[(100, true), (110, false)], 2000).is_empty());
+    }
+
+    #[test]
+    fn recognises_a_double_press() {
+        let edges = [(100, true), (200, false), (350, true), (450, false)];
+        assert_eq!(gestures(&edges, 2000), vec![Gesture::Double]);
+
+        // Too far apart, so two short presses.
+        let edges = [(100, true), (200, false), (700, true), (800, false)];
+        assert_eq!(gestures(&edges, 2000), vec![Gesture::Short, Gesture::Short]);
+    }
+
+    #[test]
+    fn fires_a_long_press_while_still_held() {
+        let fired = run(&[(100, true), (5000, false)], 6000);
+
+        assert_eq!(fired.len(), 1);
+        assert_eq!(fired[0].1, Gesture::Long);
+        assert!((1100..1110).contains(&fired[0].0), "{:?}", fired);
+    }
+
+    #[test]
+    fn a_short_press_then_a_long_one_is_a_long_press() {
+        let edges = [(100, true), (200, false), (300, true), (1500, false)];
+
+        assert_eq!(gestures(&edges, 3000), vec![Gesture::Long]);
+    }
+
+    #[test]
+    fn presses_after_a_long_one_count_again() {
+        let edges = [(100, true), (1500, false), (2000, true), (2100, false)];
+
+        assert_eq!(gestures(&edges, 3000), vec![Gesture::Long, Gesture::Short]);
+    }
+
+    #[test]
+    fn is_idle_only_without_pending_work() {
+        let mut detector = Detector::new();
+        assert!(detector.is_idle());
+
+        // Waiting for the level to settle.
+        detector.update(true, 0);
+        assert!(!detector.is_idle());
+
+        // Back before it did, so nothing happened.
+        detector.update(false, 10);
+        assert!(detector.is_idle());
+
+        // Pressed, waiting for the release or the long press.
+        detector.update(true, 100);
+        detector.update(true, 130);
+        assert!(!detector.is_idle());
+    }
+}
@@ -0,0 +1,199 @@
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+// Patterns waiting behind the one playing.
+const QUEUE: usize = 8;
+// Silence between queued patterns, so they don't blur into one.
+const GAP_MS: u32 = 150;
+
+/// A named vibration.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Pattern {
+    Tick,
+    Double,
+    Alarm,
+    Heartbeat,
+}
+
+impl Pattern {
+    /// Alternating on and off times in milliseconds, starting with on.
+    pub fn steps(&self) -> &'static [u32] {
+        match self {
+            Pattern::Tick => &[40],
+            Pattern::Double => &[60, 100, 60],
+            Pattern::Alarm => &[300, 200, 300, 200, 300],
+            Pattern::Heartbeat => &[80, 120, 140],
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Step {
+    pub on: bool,
+    pub duration_ms: u32,
+}
+
+/// Plays queued patterns one step at a time.
+pub struct Player {
+    queue: VecDeque<Pattern>,
+    current: Option<(Pattern, usize)>,
+}
+
+impl Player {
+    pub const fn new() -> Self {
+        Self {
+            queue: VecDeque::new(),
+            current: None,
+        }
+    }
+
+    /// Queues `pattern`, returning `false` when the queue is full. A pattern
+    /// already waiting last in the queue is not queued twice, so a caller
+    /// repeating it faster than it plays doesn't fill the queue.
+    pub fn push(&mut self, pattern: Pattern) -> bool {
+        if self.queue.back() == Some(&pattern) {
+            return true;
+        }
+
+        if self.queue.len() >= QUEUE {
+            return false;
+        }
+
+        self.queue.push_back(pattern);
+        true
+    }
+
+    /// Drops the queue and the pattern playing.
+    pub fn clear(&mut self) {
+        self.queue.clear();
+        self.current = None;
+    }
+
+    pub fn is_idle(&self) -> bool {
+        self.current.is_none() && self.queue.is_empty()
+    }
+
+    /// The next step to play, once the previous one has lasted its time.
+    pub fn step(&mut self) -> Option<Step> {
+        loop {
+            match self.current {
+                Some((pattern, i)) if i < pattern.steps().len() => {
+                    self.current = Some((pattern, i + 1));
+
+                    return Some(Step {
+                        on: i % 2 == 0,
+                        duration_ms: pattern.steps()[i],
+                    });
+                }
+                Some(_) => {
+                    self.current = None;
+
+                    if !self.queue.is_empty() {
+                        return Some(Step {
+                            on: false,
+                            duration_ms: GAP_MS,
+                        });
+                    }
+                }
+                None => self.current = Some((self.queue.pop_front()?, 0)),
+            }
+        }
+    }
+}
+
+impl Default for Player {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn play(player: &mut Player) -> Vec<(bool, u32)> {
+        std::iter::from_fn(|| player.step())
+            .map(|step| (step.on, step.duration_ms))
+            .collect()
+    }
+
+    #[test]
+    fn plays_a_pattern_step_by_step() {
+        let mut player = Player::new();
+        assert!(player.is_idle());
+        assert_eq!(player.step(), None);
+
+        player.push(Pattern::Heartbeat);
+
+        assert_eq!(
+            play(&mut player),
+            vec![(true, 80), (false, 120), (true, 140)]
+        );
+        assert!(player.is_idle());
+    }
+
+    #[test]
+    fn separates_queued_patterns_with_a_gap() {
+        let mut player = Player::new();
+        player.push(Pattern::Tick);
+        player.push(Pattern::Double);
+
+        assert_eq!(
+            play(&mut player),
+            vec![
+                (true, 40),
+                (false, GAP_MS),
+                (true, 60),
+                (false, 100),
+                (true, 60)
+            ]
+        );
+    }
+
+    #[test]
+    fn queues_a_repeated_pattern_once() {
+        let mut player = Player::new();
+
+        assert!(player.push(Pattern::Alarm));
+        assert!(player.push(Pattern::Alarm));
+        assert_eq!(play(&mut player).len(), Pattern::Alarm.steps().len());
+    }
+
+    #[test]
+    fn refuses_patterns_once_the_queue_is_full() {
+        let mut player = Player::new();
+
+        for i in 0..QUEUE {
+            let pattern = if i % 2 == 0 {
+                Pattern::Tick
+            } else {
+                Pattern::Double
+            };
+            assert!(player.push(pattern));
+        }
+
+        assert!(!player.push(Pattern::Alarm));
+    }
+
+    #[test]
+    fn stops_on_clear() {
+        let mut player = Player::new();
+        player.push(Pattern::Alarm);
+        player.push(Pattern::Tick);
+        player.step();
+
+        player.clear();
+
+        assert!(player.is_idle());
+        assert_eq!(player.step(), None);
+    }
+
+    #[test]
+    fn names_patterns_in_lowercase() {
+        let pattern = serde_json::from_str::<Pattern>("\"heartbeat\"").unwrap();
+
+        assert_eq!(pattern, Pattern::Heartbeat);
+        assert_eq!(serde_json::to_string(&Pattern::Tick).unwrap(), "\"tick\"");
+    }
+}
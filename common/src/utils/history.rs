@@ -0,0 +1,292 @@
+use crate::utils::vitals::Vitals;
+
+/// How many minutes each series keeps, the one in progress included.
+pub const MINUTES: usize = 60;
+const MINUTE_MS: u64 = 60 * 1000;
+
+/// How the samples taken within a minute become its point.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Aggregate {
+    Mean,
+    Sum,
+}
+
+/// A rolling buffer with one point per minute of uptime.
+#[derive(Clone, Copy, Debug)]
+pub struct Series {
+    aggregate: Aggregate,
+    // Closed minutes starting at `head`, oldest first. NaN marks a minute
+    // without samples, which keeps a point to four bytes.
+    minutes: [f32; MINUTES],
+    head: usize,
+    minute: Option<u64>,
+    sum: f32,
+    count: u32,
+}
+
+impl Series {
+    pub const fn new(aggregate: Aggregate) -> Self {
+        Self {
+            aggregate,
+            minutes: [f32::NAN; MINUTES],
+            head: 0,
+            minute: None,
+            sum: 0.0,
+            count: 0,
+        }
+    }
+
+    pub fn add(&mut self, value: f32, uptime_ms: u64) {
+        self.tick(uptime_ms);
+        self.sum += value;
+        self.count += 1;
+    }
+
+    /// Closes the minutes that ended before `uptime_ms`, leaving a gap for
+    /// those without samples.
+    pub fn tick(&mut self, uptime_ms: u64) {
+        let minute = uptime_ms / MINUTE_MS;
+
+        let Some(current) = self.minute else {
+            self.minute = Some(minute);
+            return;
+        };
+
+        if minute <= current {
+            return;
+        }
+
+        self.push(self.value());
+
+        for _ in 1..(minute - current).min(MINUTES as u64) {
+            self.push(f32::NAN);
+        }
+
+        self.minute = Some(minute);
+        self.sum = 0.0;
+        self.count = 0;
+    }
+
+    fn value(&self) -> f32 {
+        if self.count == 0 {
+            return f32::NAN;
+        }
+
+        match self.aggregate {
+            Aggregate::Mean => self.sum / self.count as f32,
+            Aggregate::Sum => self.sum,
+        }
+    }
+
+    fn push(&mut self, value: f32) {
+        self.minutes[self.head] = value;
+        self.head = (self.head + 1) % MINUTES;
+    }
+
+    /// One point per minute, oldest first and ending with the minute in
+    /// progress.
+    pub fn points(&self) -> [Option<f32>; MINUTES] {
+        let mut points = [None; MINUTES];
+
+        for (i, point) in points.iter_mut().take(MINUTES - 1).enumerate() {
+            let value = self.minutes[(self.head + 1 + i) % MINUTES];
+            *point = (!value.is_nan()).then_some(value);
+        }
+
+        let current = self.value();
+        points[MINUTES - 1] = (!current.is_nan()).then_some(current);
+        points
+    }
+
+    /// The lowest and highest points, if there are any.
+    pub fn range(&self) -> Option<(f32, f32)> {
+        self.points()
+            .into_iter()
+            .flatten()
+            .fold(None, |range, value| match range {
+                Some((min, max)) => Some((value.min(min), value.max(max))),
+                None => Some((value, value)),
+            })
+    }
+}
+
+/// The last hour of the readings shown as trends.
+#[derive(Clone, Copy, Debug)]
+pub struct History {
+    pub heart_rate: Series,
+    pub temperature: Series,
+    pub steps: Series,
+    last_steps: Option<u32>,
+}
+
+impl History {
+    pub const fn new() -> Self {
+        Self {
+            heart_rate: Series::new(Aggregate::Mean),
+            temperature: Series::new(Aggregate::Mean),
+            steps: Series::new(Aggregate::Sum),
+            last_steps: None,
+        }
+    }
+
+    /// Samples the latest readings. Steps are a running total, so each
+    /// minute gets the steps taken since the previous sample.
+    pub fn record(&mut self, vitals: &Vitals, uptime_ms: u64) {
+        match vitals.heart_rate {
+            Some(bpm) => self.heart_rate.add(bpm as f32, uptime_ms),
+            None => self.heart_rate.tick(uptime_ms),
+        }
+
+        match vitals.temperature {
+            Some(celsius) => self.temperature.add(celsius, uptime_ms),
+            None => self.temperature.tick(uptime_ms),
+        }
+
+        let steps = match self.last_steps {
+            Some(last) if vitals.steps >= last => vitals.steps - last,
+            Some(_) => vitals.steps,
+            None => 0,
+        };
+
+        self.last_steps = Some(vitals.steps);
+        self.steps.add(steps as f32, uptime_ms);
+    }
+}
+
+impl Default for History {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(minute: u64, second: u64) -> u64 {
+        minute * MINUTE_MS + second * 1000
+    }
+
+    #[test]
+    fn averages_or_sums_the_samples_of_a_minute() {
+        let mut mean = Series::new(Aggregate::Mean);
+        let mut sum = Series::new(Aggregate::Sum);
+
+        for (second, value) in [(0, 60.0), (20, 70.0), (40, 80.0)] {
+            mean.add(value, at(0, second));
+            sum.add(value, at(0, second));
+        }
+
+        assert_eq!(mean.points()[MINUTES - 1], Some(70.0));
+        assert_eq!(sum.points()[MINUTES - 1], Some(210.0));
+    }
+
+    #[test]
+    fn closes_a_minute_once_the_next_one_starts() {
+        let mut series = Series::new(Aggregate::Mean);
+        series.add(60.0, at(0, 30));
+        series.add(90.0, at(1, 0));
+
+        let points = series.points();
+        assert_eq!(points[MINUTES - 2], Some(60.0));
+        assert_eq!(points[MINUTES - 1], Some(90.0));
+    }
+
+    #[test]
+    fn leaves_gaps_for_minutes_without_samples() {
+        let mut series = Series::new(Aggregate::Mean);
+        series.add(60.0, at(0, 0));
+        series.add(80.0, at(3, 0));
+        series.tick(at(4, 0));
+
+        let points = series.points();
+        assert_eq!(
+            points[MINUTES - 5..],
+            [Some(60.0), None, None, Some(80.0), None]
+        );
+    }
+
+    #[test]
+    fn keeps_only_the_last_hour() {
+        let mut series = Series::new(Aggregate::Sum);
+
+        for minute in 0..MINUTES as u64 + 10 {
+            series.add(minute as f32, at(minute, 0));
+        }
+
+        let points = series.points();
+        assert_eq!(points[0], Some(10.0));
+        assert_eq!(points[MINUTES - 1], Some(MINUTES as f32 + 9.0));
+        assert!(points.iter().all(Option::is_some));
+    }
+
+    #[test]
+    fn forgets_everything_after_an_hour_without_samples() {
+        let mut series = Series::new(Aggregate::Mean);
+        series.add(60.0, at(0, 0));
+        series.tick(at(MINUTES as u64 * 3, 0));
+
+        assert_eq!(series.range(), None);
+    }
+
+    #[test]
+    fn ranges_over_the_points() {
+        let mut series = Series::new(Aggregate::Mean);
+        assert_eq!(series.range(), None);
+
+        for (minute, value) in [(0, 36.5), (2, 35.9), (5, 37.2)] {
+            series.add(value, at(minute, 0));
+        }
+
+        assert_eq!(series.range(), Some((35.9, 37.2)));
+    }
+
+    #[test]
+    fn records_the_steps_taken_since_the_last_sample() {
+        let mut history = History::new();
+
+        for (second, steps) in [(0, 1000), (20, 1010), (40, 1025)] {
+            let vitals = Vitals {
+                steps,
+                ..Vitals::default()
+            };
+            history.record(&vitals, at(0, second));
+        }
+
+        assert_eq!(history.steps.points()[MINUTES - 1], Some(25.0));
+    }
+
+    #[test]
+    fn counts_from_zero_after_the_counter_resets() {
+        let mut history = History::new();
+
+        for (minute, steps) in [(0, 500), (1, 520), (2, 7)] {
+            let vitals = Vitals {
+                steps,
+                ..Vitals::default()
+            };
+            history.record(&vitals, at(minute, 0));
+        }
+
+        let points = history.steps.points();
+        assert_eq!(points[MINUTES - 2..], [Some(20.0), Some(7.0)]);
+    }
+
+    #[test]
+    fn leaves_gaps_for_missing_readings() {
+        let mut history = History::new();
+        let reading = Vitals {
+            heart_rate: Some(72),
+            temperature: Some(36.4),
+            ..Vitals::default()
+        };
+
+        history.record(&reading, at(0, 0));
+        history.record(&Vitals::default(), at(1, 0));
+        history.record(&reading, at(2, 0));
+
+        let points = history.heart_rate.points();
+        assert_eq!(points[MINUTES - 3..], [Some(72.0), None, Some(72.0)]);
+        assert_eq!(history.temperature.points()[MINUTES - 2], None);
+    }
+}
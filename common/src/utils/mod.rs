@@ -0,0 +1,14 @@
+pub mod battery;
+pub mod calibration;
+pub mod check;
+pub mod clock;
+pub mod config;
+pub mod connection;
+pub mod countdown;
+pub mod gesture;
+pub mod haptics;
+pub mod history;
+pub mod rate;
+pub mod settings;
+pub mod status;
+pub mod vitals;
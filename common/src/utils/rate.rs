@@ -0,0 +1,100 @@
+use std::time::Duration;
+
+/// Token bucket allowing bursts of `capacity` and one more event every
+/// `period` after that.
+pub struct Limiter {
+    capacity: u32,
+    period: Duration,
+    tokens: u32,
+    last: Duration,
+}
+
+impl Limiter {
+    pub fn new(capacity: u32, period: Duration) -> Self {
+        Self {
+            capacity,
+            period,
+            tokens: capacity,
+            last: Duration::ZERO,
+        }
+    }
+
+    pub fn allow(&mut self, now: Duration) -> bool {
+        let elapsed = now.saturating_sub(self.last);
+        let refill = (elapsed.as_millis() / self.period.as_millis().max(1)) as u32;
+
+        if refill > 0 {
+            self.tokens = self.tokens.saturating_add(refill).min(self.capacity);
+            // A full bucket does not bank the time it spent full.
+            self.last = if self.tokens == self.capacity {
+                now
+            } else {
+                self.last + self.period * refill
+            };
+        }
+
+        if self.tokens == 0 {
+            return false;
+        }
+
+        self.tokens -= 1;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BURST: u32 = 5;
+    const REFILL: Duration = Duration::from_secs(2);
+
+    fn secs(secs: u64) -> Duration {
+        Duration::from_secs(secs)
+    }
+
+    fn allowed(limiter: &mut Limiter, count: u32, now: Duration) -> u32 {
+        (0..count).filter(|_| limiter.allow(now)).count() as u32
+    }
+
+    #[test]
+    fn allows_a_burst() {
+        let mut limiter = Limiter::new(BURST, REFILL);
+
+        assert_eq!(allowed(&mut limiter, BURST * 2, secs(0)), BURST);
+    }
+
+    #[test]
+    fn denies_until_the_next_refill() {
+        let mut limiter = Limiter::new(BURST, REFILL);
+        allowed(&mut limiter, BURST, secs(0));
+
+        assert!(!limiter.allow(secs(1)));
+        assert!(!limiter.allow(REFILL - Duration::from_millis(1)));
+        assert!(limiter.allow(REFILL));
+        assert!(!limiter.allow(REFILL));
+    }
+
+    #[test]
+    fn refills_one_event_per_period() {
+        let mut limiter = Limiter::new(BURST, REFILL);
+        allowed(&mut limiter, BURST, secs(0));
+
+        assert_eq!(allowed(&mut limiter, BURST, REFILL * 3), 3);
+
+        // Time already spent towards the next token is kept.
+        assert!(!limiter.allow(REFILL * 3 + secs(1)));
+        assert!(limiter.allow(REFILL * 4));
+    }
+
+    #[test]
+    fn refills_no_more_than_the_burst() {
+        let mut limiter = Limiter::new(BURST, REFILL);
+        allowed(&mut limiter, BURST, secs(0));
+
+        assert_eq!(allowed(&mut limiter, BURST * 2, secs(600)), BURST);
+
+        // A full bucket doesn't bank the time it sat full.
+        assert!(!limiter.allow(secs(601)));
+    }
+}
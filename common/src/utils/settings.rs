@@ -0,0 +1,74 @@
+use crate::{CLIENT_ID, HOST, PASSWORD, PORT, SSID};
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+
+const MAX_SSID: usize = 32;
+const MIN_PASSWORD: usize = 8;
+const MAX_PASSWORD: usize = 64;
+const MAX_HOST: usize = 64;
+const MAX_DEVICE: usize = 32;
+
+/// Network and broker settings entered through the provisioning portal.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Settings {
+    pub ssid: String,
+    pub password: String,
+    pub host: String,
+    pub port: u16,
+    pub device: String,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            ssid: SSID.to_string(),
+            password: PASSWORD.to_string(),
+            host: HOST.to_string(),
+            port: PORT.parse().unwrap_or(1883),
+            device: CLIENT_ID.to_string(),
+        }
+    }
+}
+
+impl Settings {
+    pub fn validate(&self) -> Result<()> {
+        if self.ssid.is_empty() || self.ssid.len() > MAX_SSID {
+            bail!("Wi-Fi name must have 1 to {} characters", MAX_SSID);
+        }
+
+        if !self.password.is_empty()
+            && !(MIN_PASSWORD..=MAX_PASSWORD).contains(&self.password.len())
+        {
+            bail!(
+                "Wi-Fi password must be empty or have {} to {} characters",
+                MIN_PASSWORD,
+                MAX_PASSWORD
+            );
+        }
+
+        let host = |c: char| c.is_ascii_alphanumeric() || c == '.' || c == '-';
+
+        if self.host.is_empty() || self.host.len() > MAX_HOST || !self.host.chars().all(host) {
+            bail!("Broker host must be a hostname or an IP address");
+        }
+
+        if self.port == 0 {
+            bail!("Broker port must be between 1 and 65535");
+        }
+
+        // The id is part of every MQTT topic, so wildcards and separators are not allowed.
+        let device = |c: char| c.is_ascii_alphanumeric() || c == '-' || c == '_';
+
+        if self.device.is_empty()
+            || self.device.len() > MAX_DEVICE
+            || !self.device.chars().all(device)
+        {
+            bail!(
+                "Device id must have 1 to {} letters, digits, '-' or '_'",
+                MAX_DEVICE
+            );
+        }
+
+        Ok(())
+    }
+}
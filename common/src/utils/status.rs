@@ -0,0 +1,71 @@
+use crate::utils::{clock::Source, connection};
+use std::sync::Mutex;
+
+// Signal strength, in dBm, at which each further bar is shown.
+const SIGNAL_BARS: [i8; 3] = [-80, -70, -60];
+// Charge, in percent, at which each further battery segment is shown.
+const BATTERY_SEGMENTS: [u8; 4] = [10, 35, 60, 85];
+
+static STATUS: Mutex<Status> = Mutex::new(Status::new());
+
+/// Link, sync and power state shown by the status icons. Each field is kept
+/// by the code that observes it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Status {
+    pub wifi: bool,
+    pub rssi: Option<i8>,
+    pub mqtt: connection::Status,
+    pub time: Source,
+    pub queue: usize,
+    pub battery: Option<u8>,
+}
+
+impl Status {
+    pub const fn new() -> Self {
+        Self {
+            wifi: false,
+            rssi: None,
+            mqtt: connection::Status::Disconnected,
+            time: Source::None,
+            queue: 0,
+            battery: None,
+        }
+    }
+
+    /// Wi-Fi bars from 0 to 3, or `None` while disconnected.
+    pub fn signal(&self) -> Option<usize> {
+        if !self.wifi {
+            return None;
+        }
+
+        let rssi = self.rssi?;
+        Some(SIGNAL_BARS.iter().filter(|bar| rssi >= **bar).count())
+    }
+
+    /// Battery segments from 0 to 4, or `None` when the level is unknown.
+    pub fn charge(&self) -> Option<usize> {
+        let battery = self.battery?;
+        Some(
+            BATTERY_SEGMENTS
+                .iter()
+                .filter(|segment| battery >= **segment)
+                .count(),
+        )
+    }
+}
+
+impl Default for Status {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub fn get() -> Status {
+    STATUS.lock().map_or(Status::new(), |status| *status)
+}
+
+pub fn update(f: impl FnOnce(&mut Status)) {
+    if let Ok(mut status) = STATUS.lock() {
+        f(&mut status);
+    }
+}
@@ -0,0 +1,9 @@
+use serde::Serialize;
+
+#[derive(Serialize, Clone, Copy, Debug, Default)]
+pub struct Vitals {
+    pub heart_rate: Option<u32>,
+    pub temperature: Option<f32>,
+    pub steps: u32,
+    pub activity: Option<&'static str>,
+}
@@ -1 +0,0 @@
-#include "ds18b20.h"
\ No newline at end of file
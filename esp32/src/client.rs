@@ -1,16 +1,39 @@
 use crate::{
+    commands,
+    network::Network,
     solver::{PAYLOADS, RED_UPDATES},
-    CLIENT_ID, HOST, PORT,
+    utils::{
+        config,
+        connection::{Action, Backoff, Connection, Event, Status},
+        settings, status, supervisor, time,
+    },
 };
-use anyhow::Result;
-use esp_idf_svc::mqtt::client::*;
+use anyhow::{anyhow, bail, Result};
+use esp_idf_svc::mqtt::client::{self as mqtt, *};
 use esp_idf_sys::*;
 use log::*;
 use std::{
-    sync::{Arc, Mutex},
+    collections::VecDeque,
+    sync::{
+        atomic::{AtomicU8, Ordering},
+        mpsc::{self, RecvTimeoutError, Sender},
+        Arc, Mutex,
+    },
     thread,
+    time::{Duration, Instant},
 };
 
+const ACK_HISTORY: usize = 64;
+const STATUS_TOPIC: &str = "status";
+const ONLINE: &str = "online";
+const OFFLINE: &str = "offline";
+const KEEP_ALIVE: Duration = Duration::from_secs(30);
+const RETRY_MIN: Duration = Duration::from_secs(1);
+const RETRY_MAX: Duration = Duration::from_secs(120);
+const POLL: Duration = Duration::from_secs(1);
+
+static STATUS: AtomicU8 = AtomicU8::new(0);
+
 pub struct MessageData<'a> {
     pub data: &'a [u8],
 }
@@ -23,22 +46,164 @@ impl<'b, 'a: 'b> MessageData<'a> {
 
 pub struct Client {
     client: Mutex<EspMqttClient<'static, ConnState<MessageImpl, EspError>>>,
+    acks: Arc<Mutex<VecDeque<MessageId>>>,
+}
+
+fn status_code(status: Status) -> u8 {
+    match status {
+        Status::Disconnected => 0,
+        Status::Connecting => 1,
+        Status::Connected => 2,
+    }
+}
+
+pub fn status() -> Status {
+    match STATUS.load(Ordering::Relaxed) {
+        2 => Status::Connected,
+        1 => Status::Connecting,
+        _ => Status::Disconnected,
+    }
+}
+
+pub fn is_connected() -> bool {
+    status() == Status::Connected
+}
+
+fn status_topic() -> String {
+    format!("{}/{}", STATUS_TOPIC, settings::get().device)
 }
 
-pub fn create_client(client: Arc<Mutex<Option<Client>>>) -> Result<()> {
-    if let Ok(mut client) = client.lock() {
-        let current = Client::new(CLIENT_ID, HOST, PORT, |_, _| {})?;
+fn subscribe_all(client: &Client) -> Result<()> {
+    for update in RED_UPDATES {
+        for driver in PAYLOADS {
+            client.subscribe(&format!("{}/{}", update, driver))?
+        }
+    }
+
+    client.subscribe(time::TIME)?;
+    client.subscribe_reliable(&commands::topic("#"))
+}
+
+/// Publishes the active configuration retained, so the server always knows
+/// what the device is running with.
+pub fn report_config(client: &Client) -> Result<()> {
+    client.publish(&config::topic(), &serde_json::to_string(&config::get())?)
+}
 
-        for update in RED_UPDATES {
-            for driver in PAYLOADS {
-                current.subscribe(&format!("{}/{}", update, driver))?
+fn create_client(generation: u32, events: Sender<(u32, Event)>) -> Result<Client> {
+    let settings = settings::get();
+
+    Client::new(
+        &settings.device,
+        &settings.host,
+        &settings.port.to_string(),
+        |topic, data| match topic {
+            Some(time::TIME) => {
+                if let Err(e) = time::offer(data.to_str()) {
+                    info!("Time offer ERROR: {:?}", e);
+                }
             }
+            Some(topic) => commands::dispatch(topic, data.to_str()),
+            None => {}
+        },
+        move |event| {
+            let _ = events.send((generation, event));
+        },
+    )
+}
+
+/// Keeps the broker connection alive, rebuilding the client with backoff
+/// whenever the network or the broker drops it.
+pub fn supervise(network: Arc<Network>, client: Arc<Mutex<Option<Client>>>) -> Result<()> {
+    let (tx, rx) = mpsc::channel::<(u32, Event)>();
+    let start = Instant::now();
+    let mut generation = 0;
+    let mut connection =
+        Connection::new(Backoff::new(RETRY_MIN, RETRY_MAX, unsafe { esp_random() }));
+
+    let t = tx.clone();
+    network.listen(move |connected| {
+        let event = if connected {
+            Event::NetworkUp
+        } else {
+            Event::NetworkDown
+        };
+        let _ = t.send((0, event));
+    })?;
+
+    let mut pending = vec![Event::NetworkUp];
+
+    loop {
+        let mut actions = Vec::new();
+
+        for event in pending.drain(..) {
+            actions.extend(connection.handle(event, start.elapsed()));
         }
 
-        *client = Some(current);
-    }
+        for action in actions {
+            info!("MQTT {:?}", action);
+
+            match action {
+                Action::Connect => {
+                    generation += 1;
 
-    Ok(())
+                    match create_client(generation, tx.clone()) {
+                        Ok(current) => {
+                            if let Ok(mut client) = client.lock() {
+                                *client = Some(current);
+                            }
+                        }
+                        Err(e) => {
+                            info!("MQTT client ERROR: {:?}", e);
+                            pending.push(Event::Disconnected);
+                        }
+                    }
+                }
+                Action::Disconnect => {
+                    if let Ok(mut client) = client.lock() {
+                        *client = None;
+                    }
+                }
+                Action::Resubscribe => {
+                    if let Ok(client) = client.lock() {
+                        if let Some(client) = client.as_ref() {
+                            subscribe_all(client)?;
+                        }
+                    }
+                }
+                Action::Announce => {
+                    if let Ok(client) = client.lock() {
+                        if let Some(client) = client.as_ref() {
+                            if let Err(e) = client.announce(ONLINE) {
+                                info!("MQTT announce ERROR: {:?}", e);
+                            }
+
+                            if let Err(e) = report_config(client) {
+                                info!("MQTT config ERROR: {:?}", e);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        STATUS.store(status_code(connection.status()), Ordering::Relaxed);
+        status::update(|status| status.mqtt = connection.status());
+
+        if !pending.is_empty() {
+            continue;
+        }
+
+        supervisor::beat_for(POLL);
+
+        match rx.recv_timeout(POLL) {
+            // Events from a client that was already replaced are stale.
+            Ok((from, event)) if from == 0 || from == generation => pending.push(event),
+            Ok(_) => {}
+            Err(RecvTimeoutError::Timeout) => pending.push(Event::Poll),
+            Err(RecvTimeoutError::Disconnected) => bail!("MQTT event channel closed"),
+        }
+    }
 }
 
 impl Client {
@@ -47,12 +212,24 @@ impl Client {
         host: &str,
         port: &str,
         mut callback: impl FnMut(Option<&str>, MessageData) + Send + 'static,
+        mut events: impl FnMut(Event) + Send + 'static,
     ) -> Result<Self> {
         info!("About to start MQTT client");
 
+        let status = status_topic();
         let conf = MqttClientConfiguration {
             client_id: Some(id),
             crt_bundle_attach: Some(esp_idf_svc::sys::esp_crt_bundle_attach),
+            keep_alive_interval: Some(KEEP_ALIVE),
+            // Keep the session so the broker holds subscriptions and QoS 1
+            // commands while we are away.
+            disable_clean_session: true,
+            lwt: Some(LwtConfiguration {
+                topic: &status,
+                payload: OFFLINE.as_bytes(),
+                qos: QoS::AtLeastOnce,
+                retain: true,
+            }),
             ..Default::default()
         };
 
@@ -61,26 +238,43 @@ impl Client {
 
         info!("MQTT client started");
 
+        let acks = Arc::new(Mutex::new(VecDeque::with_capacity(ACK_HISTORY)));
+        let a = acks.clone();
         thread::spawn(move || {
             info!("MQTT Listening for messages");
 
             while let Some(msg) = connection.next() {
                 match msg {
                     Err(e) => info!("MQTT Message ERROR: {}", e),
-                    Ok(msg) => match msg as Event<MessageImpl> {
-                        Event::Received(msg) => {
+                    Ok(msg) => match msg as mqtt::Event<MessageImpl> {
+                        mqtt::Event::Received(msg) => {
                             callback(msg.topic(), MessageData { data: msg.data() });
                         }
+                        mqtt::Event::Connected(session_present) => {
+                            events(Event::Connected { session_present });
+                        }
+                        mqtt::Event::Disconnected => events(Event::Disconnected),
+                        mqtt::Event::Published(id) => {
+                            if let Ok(mut acks) = a.lock() {
+                                if acks.len() == ACK_HISTORY {
+                                    acks.pop_front();
+                                }
+
+                                acks.push_back(id);
+                            }
+                        }
                         _ => {}
                     },
                 }
             }
 
             info!("MQTT connection loop exit");
+            events(Event::Disconnected);
         });
 
         Ok(Self {
             client: Mutex::new(client),
+            acks,
         })
     }
 
@@ -96,6 +290,20 @@ impl Client {
         Ok(())
     }
 
+    /// Subscribes with QoS 1, so the broker queues what arrives while we
+    /// are away.
+    pub fn subscribe_reliable(&self, topic: &str) -> Result<()> {
+        info!("MQTT Subscribing to {}", topic);
+
+        if let Ok(mut client) = self.client.lock() {
+            if let Err(e) = client.subscribe(topic, QoS::AtLeastOnce) {
+                info!("MQTT Subscribe ERROR: {}", e);
+            }
+        }
+
+        Ok(())
+    }
+
     pub fn publish(&self, topic: &str, message: &str) -> Result<()> {
         if let Ok(mut client) = self.client.lock() {
             if let Err(e) = client.publish(topic, QoS::AtMostOnce, true, message.as_bytes()) {
@@ -105,4 +313,29 @@ impl Client {
 
         Ok(())
     }
+
+    /// Publishes with QoS 1, returning the id the broker acknowledges.
+    pub fn publish_reliable(&self, topic: &str, message: &str) -> Result<MessageId> {
+        let mut client = self
+            .client
+            .lock()
+            .map_err(|_| anyhow!("MQTT client lock poisoned"))?;
+
+        Ok(client.publish(topic, QoS::AtLeastOnce, false, message.as_bytes())?)
+    }
+
+    pub fn is_acknowledged(&self, id: MessageId) -> bool {
+        self.acks.lock().map_or(false, |acks| acks.contains(&id))
+    }
+
+    /// Publishes the retained device status that the last will replaces.
+    pub fn announce(&self, status: &str) -> Result<()> {
+        let mut client = self
+            .client
+            .lock()
+            .map_err(|_| anyhow!("MQTT client lock poisoned"))?;
+
+        client.publish(&status_topic(), QoS::AtLeastOnce, true, status.as_bytes())?;
+        Ok(())
+    }
 }
@@ -0,0 +1,37 @@
+use crate::utils::settings;
+use anyhow::Result;
+use std::sync::Mutex;
+
+pub const COMMAND: &str = "command";
+
+type Handler = Box<dyn Fn(&str) -> Result<()> + Send + Sync>;
+
+static HANDLERS: Mutex<Vec<(String, Handler)>> = Mutex::new(Vec::new());
+
+pub fn topic(name: &str) -> String {
+    format!("{}/{}/{}", COMMAND, settings::get().device, name)
+}
+
+pub fn register<F>(name: &str, handler: F)
+where
+    F: Fn(&str) -> Result<()> + Send + Sync + 'static,
+{
+    if let Ok(mut handlers) = HANDLERS.lock() {
+        handlers.retain(|(current, _)| current != name);
+        handlers.push((name.to_string(), Box::new(handler)));
+    }
+}
+
+pub fn dispatch(topic: &str, data: &str) {
+    let prefix = format!("{}/{}/", COMMAND, settings::get().device);
+
+    if let Some(name) = topic.strip_prefix(&prefix) {
+        if let Ok(handlers) = HANDLERS.lock() {
+            for (_, handler) in handlers.iter().filter(|(current, _)| current == name) {
+                if let Err(e) = handler(data) {
+                    log::warn!("Command {} failed: {:?}", name, e);
+                }
+            }
+        }
+    }
+}
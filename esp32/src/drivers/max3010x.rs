@@ -11,7 +11,7 @@ const RESET_MASK: u8 = 0xBF;
 const RESET: u8 = 0x40;
 const FIFOCONFIG: u8 = 0x08;
 
-const SAMPLEAVG_MASK: u8 = 0xE0;
+const SAMPLEAVG_MASK: u8 = 0x1F;
 const SAMPLEAVG_1: u8 = 0x00;
 const SAMPLEAVG_2: u8 = 0x20;
 const SAMPLEAVG_4: u8 = 0x40;
@@ -64,6 +64,7 @@ const FIFOOVERFLOW: u8 = 0x05;
 const FIFOREADPTR: u8 = 0x06;
 
 const STORAGE_SIZE: usize = 4;
+const FIFO_SIZE: u8 = 32;
 const FIFODATA: u8 = 0x07;
 const I2C_BUFFER_LENGTH: usize = 32;
 
@@ -78,6 +79,13 @@ pub struct Sense {
     green: [u32; STORAGE_SIZE],
 }
 
+/// One sample taken from the FIFO.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Reading {
+    pub red: u32,
+    pub ir: u32,
+}
+
 pub struct Max3010x<I2C>
 where
     I2C: Write + Read,
@@ -85,6 +93,7 @@ where
     i2c: I2C,
     sense: Sense,
     active_leds: Byte,
+    sample_period: Duration,
 }
 
 pub struct Config {
@@ -125,6 +134,7 @@ where
                 green: [0; STORAGE_SIZE],
             },
             active_leds: 0,
+            sample_period: Duration::ZERO,
         };
 
         max3010x.setup(&config)?;
@@ -151,7 +161,7 @@ where
     }
 
     pub fn set_fifo_average(&mut self, samples: u8) -> Result<()> {
-        self.bit_mask(FIFOCONFIG, SAMPLEAVG_MASK, samples << 5)
+        self.bit_mask(FIFOCONFIG, SAMPLEAVG_MASK, samples)
     }
 
     pub fn enable_fifo_rollover(&mut self) -> Result<()> {
@@ -294,6 +304,55 @@ where
         Ok(number_of_samples as u16)
     }
 
+    /// Time between two samples in the FIFO, once averaged.
+    pub fn sample_period(&self) -> Duration {
+        self.sample_period
+    }
+
+    /// Reads every sample waiting in the FIFO, oldest first, along with how
+    /// many were overwritten before they could be read.
+    pub fn read_fifo(&mut self) -> Result<(Vec<Reading>, u8)> {
+        // Cleared by the first read from the FIFO.
+        let lost = self.read_register(I2C_ADDRESS, FIFOOVERFLOW)?;
+        let read_pointer = self.get_read_pointer()?;
+        let write_pointer = self.get_write_pointer()?;
+
+        let mut pending = (write_pointer.wrapping_sub(read_pointer) % FIFO_SIZE) as usize;
+
+        // Equal pointers after an overflow mean a full FIFO, not an empty one.
+        if pending == 0 && lost > 0 {
+            pending = FIFO_SIZE as usize;
+        }
+
+        let width = self.active_leds.max(1) as usize * 3;
+        let mut readings = Vec::with_capacity(pending);
+
+        while pending > 0 {
+            let count = pending.min(I2C_BUFFER_LENGTH / width);
+            let mut buffer = vec![0u8; count * width];
+
+            self.i2c.write(I2C_ADDRESS, &[FIFODATA])?;
+            self.i2c.read(I2C_ADDRESS, &mut buffer)?;
+
+            for sample in buffer.chunks(width) {
+                let channel = |i: usize| {
+                    sample
+                        .get(i * 3..i * 3 + 3)
+                        .map_or(0, |b| u32::from_be_bytes([0, b[0], b[1], b[2]]) & 0x3FFFF)
+                };
+
+                readings.push(Reading {
+                    red: channel(0),
+                    ir: channel(1),
+                });
+            }
+
+            pending -= count;
+        }
+
+        Ok((readings, lost))
+    }
+
     pub fn safe_check(&mut self, max_time_to_clock: u8) -> Result<bool> {
         let mark_time = Instant::now();
 
@@ -373,6 +432,24 @@ where
             _ => self.set_sample_rate(SAMPLERATE_50)?,
         }
 
+        // The rate and average the matches above settle on.
+        let rate: u64 = match config.sample_rate {
+            100..=199 => 100,
+            200..=399 => 200,
+            400..=799 => 400,
+            800..=999 => 800,
+            1000..=1599 => 1000,
+            1600..=3199 => 1600,
+            3200 => 3200,
+            _ => 50,
+        };
+        let average: u64 = match config.sample_average {
+            1 | 2 | 4 | 8 | 16 | 32 => config.sample_average as u64,
+            _ => 4,
+        };
+
+        self.sample_period = Duration::from_micros(1_000_000 * average / rate);
+
         match config.pulse_width {
             0..=117 => self.set_pulse_width(PULSEWIDTH_69)?,
             118..=214 => self.set_pulse_width(PULSEWIDTH_118)?,
@@ -1,4 +1,5 @@
 pub mod max3010x;
 pub mod ssd1306;
-pub mod ds18b20;
-pub mod mpu6050;
\ No newline at end of file
+pub mod mpu6050;
+
+pub use common::drivers::{ds18b20, onewire};
@@ -1,10 +1,13 @@
-use crate::handlers::mpu6050::{Accel, Rotation};
+use crate::analysis::fall::Vector;
+use crate::handlers::mpu6050::{Accel, Gyro, Rotation};
 use anyhow::{anyhow, Result};
 use embedded_hal::blocking::i2c::{Write, WriteRead};
 use esp_idf_svc::hal::delay::*;
 use mpu6050_dmp::{address::Address, quaternion::Quaternion, sensor, yaw_pitch_roll::YawPitchRoll};
 use std::{error::Error, fmt::Debug};
 
+pub use common::drivers::mpu6050::*;
+
 impl Debug for Accel {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("Accel")
@@ -15,6 +18,16 @@ impl Debug for Accel {
     }
 }
 
+impl Debug for Gyro {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Gyro")
+            .field("x", &self.x)
+            .field("y", &self.y)
+            .field("z", &self.z)
+            .finish()
+    }
+}
+
 impl Debug for Rotation {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("Rotation")
@@ -32,16 +45,22 @@ where
     <I2C as WriteRead>::Error: Error + Send + Sync + 'static,
 {
     sensor: sensor::Mpu6050<I2C>,
+    imu: Imu<I2C>,
     buf: [u8; 64],
 }
 
 impl<I2C> Mpu6050<I2C>
 where
-    I2C: Write + WriteRead,
+    I2C: Write + WriteRead + Clone,
     <I2C as Write>::Error: Error + Send + Sync + 'static,
     <I2C as WriteRead>::Error: Error + Send + Sync + 'static,
 {
     pub fn new(i2c: I2C) -> Result<Self> {
+        Self::with_config(i2c, Config::default())
+    }
+
+    pub fn with_config(i2c: I2C, config: Config) -> Result<Self> {
+        let mut imu = Imu::new(i2c.clone(), ADDRESS);
         let mut sensor = match sensor::Mpu6050::new(i2c, Address::default()) {
             Ok(sensor) => Ok(sensor),
             Err(_) => Err(anyhow!("Failed to initialize MPU6050")),
@@ -54,19 +73,35 @@ where
             return Err(anyhow!("Failed to initialize MPU6050: {:?}", e));
         }
 
-        Ok(Self { sensor, buf })
+        imu.configure(config)?;
+
+        Ok(Self { sensor, imu, buf })
+    }
+
+    pub fn imu(&mut self) -> &mut Imu<I2C> {
+        &mut self.imu
     }
 
     pub fn get_accel(&mut self) -> Result<Accel> {
-        if let Ok(a) = self.sensor.accel() {
-            return Ok(Accel {
-                x: a.x(),
-                y: a.y(),
-                z: a.z(),
-            });
-        }
+        let [x, y, z] = self.imu.accel()?;
+        Ok(Accel { x, y, z })
+    }
+
+    pub fn get_gyro(&mut self) -> Result<Gyro> {
+        let [x, y, z] = self.imu.gyro()?;
+        Ok(Gyro { x, y, z })
+    }
+
+    pub fn get_accel_g(&mut self) -> Result<Vector> {
+        self.imu.accel_g()
+    }
+
+    pub fn get_gyro_dps(&mut self) -> Result<Vector> {
+        self.imu.gyro_dps()
+    }
 
-        return Err(anyhow!("Failed get accel"));
+    pub fn get_temperature(&mut self) -> Result<f32> {
+        self.imu.temperature()
     }
 
     pub fn get_rotation(&mut self) -> Result<Rotation> {
@@ -0,0 +1,58 @@
+use crate::{
+    handlers::{
+        button::{Priority, Report},
+        haptics,
+        sos::{self, SOS},
+    },
+    solver::{Message, Solver},
+    utils::{countdown::State, haptics::Pattern, supervisor},
+};
+use anyhow::Result;
+use std::{sync::Arc, thread, time::Duration};
+
+const PERIOD: Duration = Duration::from_secs(1);
+const IDLE: Duration = Duration::from_millis(200);
+
+fn describe(reason: &str) -> String {
+    match reason {
+        "fall" => "Fall detected and not cancelled".to_string(),
+        _ => format!("Alarm '{}' not cancelled", reason),
+    }
+}
+
+pub fn alarm(solver: Arc<Solver>) -> Result<()> {
+    loop {
+        supervisor::beat();
+
+        match solver.countdown.poll() {
+            State::Running { reason, remaining } => {
+                log::info!("{} alarm in {}s", reason, remaining.as_secs());
+
+                haptics::play(Pattern::Alarm);
+                thread::sleep(PERIOD);
+            }
+            State::Expired { reason } if reason == SOS => {
+                haptics::stop();
+                sos::send(solver.clone());
+            }
+            State::Expired { reason } => {
+                haptics::stop();
+                log::info!("{} alarm expired, sending report", reason);
+
+                solver.send_to_database(Message::new(Report {
+                    status: reason.clone(),
+                    description: describe(&reason),
+                    priority: Priority::Normal,
+                    vitals: None,
+                }))?;
+                solver.send_to_socket(Message::new(Report {
+                    status: reason.clone(),
+                    description: describe(&reason),
+                    priority: Priority::Normal,
+                    vitals: None,
+                }))?;
+            }
+            State::Idle => thread::sleep(IDLE),
+        }
+    }
+}
@@ -1,38 +1,135 @@
-use crate::solver::{Message, Solver};
+use crate::{
+    handlers::haptics,
+    solver::Solver,
+    utils::{
+        gesture::{Detector, Gesture},
+        haptics::Pattern,
+        supervisor, time,
+        vitals::Vitals,
+    },
+};
 use anyhow::Result;
-use esp_idf_svc::hal::gpio::{AnyIOPin, PinDriver};
+use esp_idf_svc::hal::{
+    delay::TickType,
+    gpio::{AnyIOPin, InputPin, InterruptType, PinDriver},
+    peripheral::Peripheral,
+    task::notification::Notification,
+};
 use serde::{Deserialize, Serialize};
-use std::{sync::Arc, thread, time::Duration};
+use std::{
+    num::NonZeroU32,
+    sync::{Arc, Mutex},
+    thread,
+    time::{Duration, Instant},
+};
 
-#[derive(Serialize, Deserialize)]
+// While a gesture is in progress the level is sampled at this rate, so the
+// debounce and timeouts resolve without further edges.
+const POLL: Duration = Duration::from_millis(10);
+const IDLE: Duration = Duration::from_secs(5);
+// How long the button must be held while the device boots to open the
+// provisioning portal, so no gesture while worn can trigger it.
+const BOOT_HOLD: Duration = Duration::from_secs(3);
+
+type Handler = Box<dyn Fn() -> Result<bool> + Send + Sync>;
+
+static SUBSCRIBERS: Mutex<Vec<(Gesture, Handler)>> = Mutex::new(Vec::new());
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Priority {
+    #[default]
+    Normal,
+    High,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
 pub struct Report {
     pub status: String,
     pub description: String,
+    #[serde(default)]
+    pub priority: Priority,
+    /// The readings when the report was raised, for reports about the
+    /// wearer.
+    #[serde(skip_deserializing, skip_serializing_if = "Option::is_none")]
+    pub vitals: Option<Vitals>,
 }
 
-pub fn button(pin: AnyIOPin, solver: Arc<Solver>) -> Result<()> {
-    let btn = PinDriver::input(pin)?;
-    let mut status = false;
+/// Calls `handler` on every `gesture`. Subscribers run in the order they
+/// subscribed until one returns `true`, meaning it handled the gesture.
+pub fn subscribe<F>(gesture: Gesture, handler: F)
+where
+    F: Fn() -> Result<bool> + Send + Sync + 'static,
+{
+    if let Ok(mut subscribers) = SUBSCRIBERS.lock() {
+        subscribers.push((gesture, Box::new(handler)));
+    }
+}
 
-    loop {
-        if btn.is_high() {
-            status = true;
+fn dispatch(gesture: Gesture) {
+    if let Ok(subscribers) = SUBSCRIBERS.lock() {
+        for (_, handler) in subscribers
+            .iter()
+            .filter(|(current, _)| *current == gesture)
+        {
+            match handler() {
+                Ok(true) => break,
+                Ok(false) => {}
+                Err(e) => log::warn!("Button {:?} handler failed: {:?}", gesture, e),
+            }
         }
+    }
+}
+
+/// Whether the button is pressed from boot until `BOOT_HOLD` has passed.
+/// Returns at once when it is not pressed.
+pub fn held_at_boot<P: InputPin>(pin: impl Peripheral<P = P>) -> Result<bool> {
+    let btn = PinDriver::input(pin)?;
+    let started = Instant::now();
 
-        if status && btn.is_low() {
-            status = false;
-            log::info!("Button pressed");
-
-            solver.send_to_database(Message::new(Report {
-                status: "warning".to_string(),
-                description: "Ocurrio algo grave".to_string(),
-            }))?;
-            solver.send_to_socket(Message::new(Report {
-                status: "warning".to_string(),
-                description: "Ocurrio algo grave".to_string(),
-            }))?;
+    while started.elapsed() < BOOT_HOLD {
+        if btn.is_low() {
+            return Ok(false);
         }
 
-        thread::sleep(Duration::from_millis(200));
+        thread::sleep(POLL);
+    }
+
+    Ok(true)
+}
+
+pub fn button(pin: AnyIOPin, _solver: Arc<Solver>) -> Result<()> {
+    let mut btn = PinDriver::input(pin)?;
+    let notification = Notification::new();
+    let notifier = notification.notifier();
+    let mut detector = Detector::new();
+
+    btn.set_interrupt_type(InterruptType::AnyEdge)?;
+
+    unsafe {
+        btn.subscribe(move || {
+            notifier.notify_and_yield(NonZeroU32::MIN);
+        })?;
+    }
+
+    loop {
+        // The interrupt disables itself after firing.
+        btn.enable_interrupt()?;
+
+        let timeout = if detector.is_idle() { IDLE } else { POLL };
+        supervisor::beat_for(timeout);
+        notification.wait(TickType::from(timeout).ticks());
+
+        if let Some(gesture) = detector.update(btn.is_high(), time::uptime_ms()) {
+            log::info!("Button {:?}", gesture);
+            dispatch(gesture);
+
+            // Played after the subscribers, so it survives one that stops
+            // the haptics.
+            haptics::play(match gesture {
+                Gesture::Double => Pattern::Double,
+                _ => Pattern::Tick,
+            });
+        }
     }
 }
@@ -1,67 +1,308 @@
 use crate::{
-    drivers::ds18b20::Ds18b20 as Sensor,
+    commands,
+    drivers::{
+        ds18b20::{rom_id, Ds18b20 as Driver},
+        onewire::OneWire,
+    },
+    handlers::button::{Priority, Report},
     solver::{Message, Solver},
+    utils::{
+        calibration::{Calibrations, Command},
+        nvs,
+        scheduler::{self, Scheduler},
+    },
+};
+use anyhow::{anyhow, bail, Result};
+use esp_idf_svc::hal::{
+    delay::Ets,
+    gpio::{AnyIOPin, InputOutput, PinDriver},
 };
-use anyhow::Result;
-use esp_idf_svc::hal::gpio::AnyIOPin;
 use serde::{Deserialize, Serialize};
 use std::{
+    collections::{HashMap, HashSet},
     sync::{Arc, Mutex},
-    thread,
-    time::Duration,
 };
 
-const FACTOR: f32 = 1.15;
+type Sensor = Driver<PinDriver<'static, AnyIOPin, InputOutput>, Ets>;
+
+const CALIBRATION: &str = "calibration";
+const ALARM: &str = "alarm";
+const MIN_TEMPERATURE: f32 = -55.0;
+const MAX_TEMPERATURE: f32 = 125.0;
+// Failed samples in a row before the wearer's temperature counts as lost.
+const FAILURES_REPORTED: u32 = 30;
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Clone)]
 pub struct Ds18b20 {
+    pub rom: String,
     pub temperature: f32,
+    pub raw: f32,
 }
 
-pub fn ds18b20(pin: AnyIOPin, solver: Arc<Solver>) -> Result<()> {
-    let ds18b20 = Arc::new(Mutex::new(Sensor::new(pin)?));
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Alarm {
+    #[serde(default)]
+    pub rom: Option<String>,
+    pub high: f32,
+    pub low: f32,
+}
 
-    let d = ds18b20.clone();
-    let s = solver.clone();
-    thread::spawn(move || {
-        let ds18b20 = Arc::clone(&d);
-        let solver = Arc::clone(&s);
-
-        loop {
-            if let Ok(mut ds18b20) = ds18b20.lock() {
-                if let Ok(mut temperature) = ds18b20.get_temp() {
-                    temperature *= FACTOR;
-                    log::info!("SOCKET => temperature: {}", temperature);
-                    let _ = solver.send_to_socket(Message::new(Ds18b20 { temperature }));
-                } else {
-                    log::info!("Error reading sensor");
-                }
+impl Alarm {
+    fn validate(&self) -> Result<()> {
+        let range = MIN_TEMPERATURE..=MAX_TEMPERATURE;
+
+        if !range.contains(&self.high) || !range.contains(&self.low) {
+            bail!("Alarm thresholds out of range");
+        }
+
+        if self.low >= self.high {
+            bail!("Alarm low threshold must be below the high threshold");
+        }
+
+        Ok(())
+    }
+}
+
+/// Alarm thresholds by probe, as stored on the probe itself.
+type Thresholds = Mutex<HashMap<String, (f32, f32)>>;
+
+fn set_alarm(sensor: &Mutex<Sensor>, thresholds: &Thresholds, alarm: &Alarm) -> Result<()> {
+    alarm.validate()?;
+
+    if let Ok(mut sensor) = sensor.lock() {
+        let roms = sensor
+            .roms()
+            .iter()
+            .filter(|rom| alarm.rom.as_ref().map_or(true, |id| *id == rom_id(rom)))
+            .copied()
+            .collect::<Vec<_>>();
+
+        if roms.is_empty() {
+            bail!("Unknown probe {:?}", alarm.rom);
+        }
+
+        for rom in roms {
+            sensor.set_alarm(&rom, alarm.high, alarm.low)?;
+
+            // The probe keeps whole degrees, so read back what it stored.
+            let stored = sensor.get_alarm(&rom)?;
+            log::info!("Alarm set for {}: {:?}", rom_id(&rom), stored);
+
+            if let Ok(mut thresholds) = thresholds.lock() {
+                thresholds.insert(rom_id(&rom), stored);
             }
+        }
+    }
 
-            thread::sleep(Duration::from_secs(2));
+    Ok(())
+}
+
+fn load_calibrations() -> Calibrations {
+    match nvs::get::<Calibrations>(CALIBRATION) {
+        Ok(calibrations) => calibrations.unwrap_or_default(),
+        Err(e) => {
+            log::info!("Failed to load calibrations: {:?}", e);
+            Calibrations::default()
         }
-    });
+    }
+}
 
-    let d = ds18b20.clone();
-    let s = solver.clone();
-    thread::spawn(move || {
-        let ds18b20 = Arc::clone(&d);
-        let solver = Arc::clone(&s);
-
-        loop {
-            if let Ok(mut ds18b20) = ds18b20.lock() {
-                if let Ok(mut temperature) = ds18b20.get_temp() {
-                    temperature *= FACTOR;
-                    log::info!("DATABASE => temperature: {}", temperature);
-                    let _ = solver.send_to_database(Message::new(Ds18b20 { temperature }));
-                } else {
-                    log::info!("Error reading sensor");
+struct Probes {
+    ds18b20: Arc<Mutex<Sensor>>,
+    calibrations: Arc<Mutex<Calibrations>>,
+    thresholds: Arc<Thresholds>,
+}
+
+struct Tripped {
+    rom: String,
+    temperature: f32,
+    high: f32,
+    low: f32,
+}
+
+struct Sample {
+    values: Vec<Ds18b20>,
+    tripped: Vec<Tripped>,
+}
+
+impl scheduler::Sensor for Probes {
+    type Sample = Sample;
+
+    fn name(&self) -> &'static str {
+        "ds18b20"
+    }
+
+    fn init(&mut self) -> Result<()> {
+        self.ds18b20
+            .lock()
+            .map_err(|_| anyhow!("Failed to lock ds18b20"))?
+            .begin()
+    }
+
+    fn sample(&mut self) -> Result<Sample> {
+        let mut sensor = self
+            .ds18b20
+            .lock()
+            .map_err(|_| anyhow!("Failed to lock ds18b20"))?;
+        let mut values = Vec::new();
+
+        for (rom, raw) in sensor.get_temps() {
+            let rom = rom_id(&rom);
+
+            if let Ok(raw) = raw {
+                let temperature = self
+                    .calibrations
+                    .lock()
+                    .map_or(raw, |c| c.get(&rom).apply(raw));
+                log::info!("{}: {}, raw: {}", rom, temperature, raw);
+
+                values.push(Ds18b20 {
+                    rom,
+                    temperature,
+                    raw,
+                });
+            } else {
+                log::info!("Error reading sensor {}", rom);
+            }
+        }
+
+        if values.is_empty() {
+            bail!("No ds18b20 probe answered");
+        }
+
+        // Checked here rather than with an alarm search, which would compare
+        // the thresholds with the uncalibrated reading.
+        let mut tripped = Vec::new();
+
+        for value in &values {
+            let Some(rom) = sensor
+                .roms()
+                .iter()
+                .find(|rom| rom_id(rom) == value.rom)
+                .copied()
+            else {
+                continue;
+            };
+
+            let thresholds = self.thresholds.lock().ok().and_then(|mut thresholds| {
+                if !thresholds.contains_key(&value.rom) {
+                    match sensor.get_alarm(&rom) {
+                        Ok(stored) => {
+                            thresholds.insert(value.rom.clone(), stored);
+                        }
+                        Err(e) => log::info!("Error reading alarm of {}: {:?}", value.rom, e),
+                    }
+                }
+
+                thresholds.get(&value.rom).copied()
+            });
+
+            if let Some((high, low)) = thresholds {
+                if value.temperature > high || value.temperature < low {
+                    tripped.push(Tripped {
+                        rom: value.rom.clone(),
+                        temperature: value.temperature,
+                        high,
+                        low,
+                    });
                 }
             }
+        }
+
+        Ok(Sample { values, tripped })
+    }
+}
+
+fn report_alarms(tripped: &[Tripped], alarmed: &mut HashSet<String>, solver: &Solver) {
+    for probe in tripped {
+        if alarmed.insert(probe.rom.clone()) {
+            let report = Report {
+                status: "temperature".to_string(),
+                description: format!(
+                    "Probe {} at {:.1} °C outside {:.0}..{:.0} °C",
+                    probe.rom, probe.temperature, probe.low, probe.high
+                ),
+                priority: Priority::Normal,
+                vitals: None,
+            };
 
-            thread::sleep(Duration::from_secs(5));
+            log::info!("ALARM => {}", report.description);
+            let _ = solver.send_to_socket(Message::new(report.clone()));
+            let _ = solver.send_to_database(Message::new(report));
         }
+    }
+
+    alarmed.retain(|id| tripped.iter().any(|probe| probe.rom == *id));
+}
+
+fn report_failing(failures: u32, solver: &Solver) {
+    if failures != FAILURES_REPORTED {
+        return;
+    }
+
+    let report = Report {
+        status: "temperature".to_string(),
+        description: format!("No temperature reading for the last {} samples", failures),
+        priority: Priority::Normal,
+        vitals: None,
+    };
+
+    log::info!("ALARM => {}", report.description);
+    let _ = solver.send_to_socket(Message::new(report.clone()));
+    let _ = solver.send_to_database(Message::new(report));
+}
+
+pub fn ds18b20(pin: AnyIOPin, solver: Arc<Solver>) -> Result<()> {
+    let bus = OneWire::new(PinDriver::input_output_od(pin)?, Ets)?;
+    let ds18b20 = Arc::new(Mutex::new(Sensor::new(bus)?));
+    let calibrations = Arc::new(Mutex::new(load_calibrations()));
+    let thresholds = Arc::new(Mutex::new(HashMap::new()));
+
+    let c = calibrations.clone();
+    commands::register(CALIBRATION, move |data| {
+        let command = serde_json::from_str::<Command>(data)?;
+        command.calibration.validate()?;
+
+        if let Ok(mut calibrations) = c.lock() {
+            calibrations.set(command.rom.as_deref(), command.calibration);
+            nvs::set(CALIBRATION, &*calibrations)?;
+        }
+
+        log::info!("Calibration updated: {:?}", command);
+        Ok(())
+    });
+
+    let d = ds18b20.clone();
+    let t = thresholds.clone();
+    commands::register(ALARM, move |data| {
+        set_alarm(&d, &t, &serde_json::from_str::<Alarm>(data)?)
     });
 
+    let probes = Probes {
+        ds18b20,
+        calibrations,
+        thresholds,
+    };
+    let mut alarmed = HashSet::new();
+
+    Scheduler::new(probes, |c| c.ds18b20.tick())
+        .sink(
+            |_| 1,
+            move |sample, solver| {
+                report_alarms(&sample.tripped, &mut alarmed, solver);
+            },
+        )
+        .failing(|failures, solver| report_failing(*failures, solver))
+        .display(
+            |_| 1,
+            |sample, vitals| vitals.temperature = sample.values.first().map(|v| v.temperature),
+        )
+        .live(|c| c.ds18b20.live_every(), |sample| sample.values.clone())
+        .persisted(
+            |c| c.ds18b20.persist_every(),
+            |sample| sample.values.clone(),
+        )
+        .spawn(solver);
+
     Ok(())
 }
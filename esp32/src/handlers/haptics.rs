@@ -0,0 +1,69 @@
+use crate::{
+    solver::Solver,
+    utils::{
+        haptics::{Pattern, Player},
+        supervisor,
+    },
+};
+use anyhow::Result;
+use esp_idf_svc::hal::gpio::{AnyIOPin, PinDriver};
+use serde::{Deserialize, Serialize};
+use std::{
+    sync::{Arc, Mutex},
+    thread,
+    time::Duration,
+};
+
+pub const HAPTICS: &str = "haptics";
+
+const IDLE: Duration = Duration::from_millis(50);
+
+static PLAYER: Mutex<Player> = Mutex::new(Player::new());
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Command {
+    pub pattern: Pattern,
+}
+
+/// Queues `pattern` without waiting for it to play.
+pub fn play(pattern: Pattern) {
+    if let Ok(mut player) = PLAYER.lock() {
+        if !player.push(pattern) {
+            log::warn!("Haptics queue full, dropping {:?}", pattern);
+        }
+    }
+}
+
+/// Stops the pattern playing and drops the ones queued.
+pub fn stop() {
+    if let Ok(mut player) = PLAYER.lock() {
+        player.clear();
+    }
+}
+
+/// Owns the vibration motor and plays the queue.
+pub fn haptics(pin: AnyIOPin, _solver: Arc<Solver>) -> Result<()> {
+    let mut motor = PinDriver::output(pin)?;
+    motor.set_low()?;
+
+    loop {
+        supervisor::beat();
+        let step = PLAYER.lock().ok().and_then(|mut player| player.step());
+
+        match step {
+            Some(step) => {
+                if step.on {
+                    motor.set_high()?;
+                } else {
+                    motor.set_low()?;
+                }
+
+                thread::sleep(Duration::from_millis(step.duration_ms as u64));
+            }
+            None => {
+                motor.set_low()?;
+                thread::sleep(IDLE);
+            }
+        }
+    }
+}
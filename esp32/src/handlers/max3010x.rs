@@ -1,44 +1,59 @@
 use crate::{
-    drivers::max3010x::{Config, Max3010x as Sensor},
+    analysis::hrv::{self, Intervals, Metrics},
+    drivers::max3010x::{Config, Max3010x as Sensor, Reading},
     solver::{Message, Solver},
+    utils::{
+        config,
+        scheduler::{self, Scheduler},
+    },
 };
-use anyhow::{anyhow, Result};
+use anyhow::Result;
 use embedded_hal::blocking::i2c::{Read, Write};
 use log::info;
 use serde::{Deserialize, Serialize};
 use std::{
     error::Error,
-    sync::{Arc, Mutex},
-    thread,
-    time::Duration,
-    time::Instant,
+    sync::Arc,
     collections::VecDeque
 };
 
-const THRESHOLD: u32 = 200;
+const HRV_WINDOW: usize = 60;
+const HRV_MIN_INTERVALS: usize = 10;
+// How long the finger must be missing before the beats so far are dropped,
+// so a single noisy sample doesn't restart the HRV window.
+const FINGER_LOST_MS: u64 = 2_000;
+// The fastest heart rate the beat gate lets through.
+const MAX_BPM: f32 = (60_000 / hrv::MIN_INTERVAL_MS) as f32;
 
 struct HeartRateMonitor {
-    last_beat_time: Option<Instant>,
+    last_beat_ms: Option<u64>,
+    last_finger_ms: Option<u64>,
     bpm: f32,
     readings: VecDeque<u32>,
     total_ir: u32,
     last_ir_value: u32,
     finger_detected: bool,
+    intervals: Intervals,
+    config: config::Max3010x,
 }
 
 impl HeartRateMonitor {
     fn new() -> HeartRateMonitor {
         HeartRateMonitor {
-            last_beat_time: None,
+            last_beat_ms: None,
+            last_finger_ms: None,
             bpm: 0.0,
             readings: VecDeque::with_capacity(WINDOW_SIZE),
             total_ir: 0,
             last_ir_value: 0,
             finger_detected: false,
+            intervals: Intervals::new(HRV_WINDOW),
+            config: config::get().max3010x,
         }
     }
 
-    fn update(&mut self, ir: u32, red: u32) {
+    /// Takes a reading sampled at `at_ms` on the sensor's own clock.
+    fn update(&mut self, ir: u32, red: u32, at_ms: u64) {
         if self.readings.len() == WINDOW_SIZE {
             self.total_ir -= self.readings.pop_front().unwrap();
         }
@@ -46,125 +61,181 @@ impl HeartRateMonitor {
         self.total_ir += ir;
 
         let average_ir = self.total_ir as f32 / self.readings.len() as f32;
-        self.finger_detected = self.is_finger_detected(ir, red, average_ir);
+
+        if self.is_finger_detected(ir, red, average_ir) {
+            self.last_finger_ms = Some(at_ms);
+        }
+
+        self.finger_detected = self.last_finger_ms.is_some_and(|last| at_ms - last < FINGER_LOST_MS);
 
         if !self.finger_detected {
-            self.bpm = 0.0;
+            if self.last_finger_ms.take().is_some() {
+                self.bpm = 0.0;
+                self.last_beat_ms = None;
+                self.intervals.clear();
+            }
             return;
         }
 
         let filtered_ir = self.filter_ir_signal(ir);
 
-        if self.is_peak(filtered_ir, self.last_ir_value) && self.last_beat_time.map_or(true, |last_time| Instant::now().duration_since(last_time).as_secs_f32() >= MIN_INTERVAL) {
-            let now = Instant::now();
-            self.bpm = self.last_beat_time.map_or(0.0, |last_time| 60.0 / now.duration_since(last_time).as_secs_f32());
-            self.last_beat_time = Some(now);
+        if self.is_peak(filtered_ir, self.last_ir_value) && self.last_beat_ms.map_or(true, |last| at_ms - last >= self.config.min_interval_ms as u64) {
+            if let Some(last) = self.last_beat_ms {
+                let interval = (at_ms - last) as f32;
+                self.bpm = 60_000.0 / interval;
+                self.intervals.push(interval);
+            }
+
+            self.last_beat_ms = Some(at_ms);
         }
 
         self.last_ir_value = filtered_ir;
     }
-
     fn get_bpm(&self) -> f32 {
         self.bpm
     }
 
+    fn get_hrv(&self) -> Option<Metrics> {
+        self.intervals.metrics(HRV_MIN_INTERVALS)
+    }
+
     fn filter_ir_signal(&self, ir: u32) -> u32 {
         ir
     }
 
     fn is_peak(&self, current: u32, previous: u32) -> bool {
-        current > previous && current > PEAK_THRESHOLD
+        current > previous && current > self.config.peak_threshold
     }
 
     fn is_finger_detected(&self, ir: u32, red: u32, average_ir: f32) -> bool {
-        ir > MIN_IR_VALUE && red > MIN_RED_VALUE && (ir as f32 - average_ir).abs() > VARIABILITY_THRESHOLD
+        ir > self.config.min_ir && red > self.config.min_red && (ir as f32 - average_ir).abs() > self.config.variability
     }
 }
 
-const WINDOW_SIZE: usize = 10;
-const PEAK_THRESHOLD: u32 = 50000;
-const MIN_IR_VALUE: u32 = 20000;
-const MIN_RED_VALUE: u32 = 20000;
-const VARIABILITY_THRESHOLD: f32 = 1000.0; 
-const MIN_INTERVAL: f32 = 0.5;
+// About a second of readings at the sensor's 100 Hz.
+const WINDOW_SIZE: usize = 100;
 
 #[derive(Serialize, Deserialize)]
 pub struct Max3010x {
     pub heart_rate: u32
 }
 
-pub fn max3010x<I2C>(i2c: I2C, solver: Arc<Solver>) -> Result<()>
+#[derive(Serialize, Deserialize)]
+pub struct Hrv {
+    pub rmssd: f32,
+    pub sdnn: f32,
+    pub pnn50: f32,
+    pub intervals: u32,
+}
+
+impl From<Metrics> for Hrv {
+    fn from(metrics: Metrics) -> Self {
+        Self {
+            rmssd: metrics.rmssd,
+            sdnn: metrics.sdnn,
+            pnn50: metrics.pnn50,
+            intervals: metrics.intervals as u32,
+        }
+    }
+}
+
+struct Pulse<I2C>
 where
     I2C: Write + Read + Send + Sync + Clone + 'static,
     <I2C as Write>::Error: Error + Send + Sync + Sized + 'static,
     <I2C as Read>::Error: Error + Send + Sync + Sized + 'static,
 {
-    let max3010x = Arc::new(Mutex::new(Sensor::new(
-        i2c.clone(),
-        &Config {
-            ..Default::default()
-        },
-    )?));
-
-    let m = max3010x.clone();
-    let s = solver.clone();
-    thread::spawn(move || {
-        let solver = Arc::clone(&s);
-        let mut red;
-        let mut ir;
-        let mut monitor = HeartRateMonitor::new();
-
-        loop {
-            if let Ok(mut max3010x) = m.lock() {
-                red = max3010x.get_red();
-                ir = max3010x.get_ir();
-
-                if let Ok((red, ir)) = red.and_then(|red| ir.map(|ir| (red, ir))) {
-                    monitor.update(ir, red);
-
-                    let bpm = (monitor.get_bpm() - 85.0).abs();
-                    info!("BPM: {}", bpm);
-                    info!("SOCKET => red: {}, ir: {}", red, ir);
-                    let _ = solver.send_to_socket(Message::new(Max3010x { 
-                        heart_rate: bpm as u32
-                     }));
-                } else {
-                    info!("Error reading sensor");
-                }
-            }
+    i2c: I2C,
+    max3010x: Sensor<I2C>,
+    monitor: HeartRateMonitor,
+    // Position of the next FIFO sample on the sensor's clock.
+    clock_ms: u64,
+}
 
-            thread::sleep(Duration::from_millis(100));
-        }
-    });
-
-    let m = max3010x.clone();
-    let s = solver.clone();
-    let handle2 = thread::spawn(move || {
-        let solver = Arc::clone(&s);
-        let mut monitor = HeartRateMonitor::new();
-        let mut red;
-        let mut ir;
-
-        loop {
-            if let Ok(mut max3010x) = m.lock() {
-                red = max3010x.get_red();
-                ir = max3010x.get_ir();
-
-                if let Ok((red, ir)) = red.and_then(|red| ir.map(|ir| (red, ir))) {
-                    monitor.update(ir, red);
-                    let bpm = (monitor.get_bpm() - 85.0).abs();
-                    info!("DATABASE => red: {}, ir: {}", red, ir);
-                    let _ = solver.send_to_database(Message::new(Max3010x { 
-                        heart_rate: bpm as u32
-                     }));
-                } else {
-                    info!("Error reading sensor");
-                }
-            }
+struct Sample {
+    heart_rate: Option<u32>,
+    bpm: u32,
+    hrv: Option<Metrics>,
+}
+
+impl<I2C> scheduler::Sensor for Pulse<I2C>
+where
+    I2C: Write + Read + Send + Sync + Clone + 'static,
+    <I2C as Write>::Error: Error + Send + Sync + Sized + 'static,
+    <I2C as Read>::Error: Error + Send + Sync + Sized + 'static,
+{
+    type Sample = Sample;
+
+    fn name(&self) -> &'static str {
+        "max3010x"
+    }
 
-            thread::sleep(Duration::from_secs(2));
+    fn init(&mut self) -> Result<()> {
+        self.max3010x = Sensor::new(self.i2c.clone(), &Config::default())?;
+        self.monitor = HeartRateMonitor::new();
+        self.clock_ms = 0;
+        Ok(())
+    }
+
+    fn sample(&mut self) -> Result<Sample> {
+        let period = self.max3010x.sample_period().as_millis() as u64;
+        let (readings, lost) = self.max3010x.read_fifo()?;
+        let last = readings.last().copied().unwrap_or_default();
+
+        self.monitor.config = config::get().max3010x;
+        self.clock_ms += lost as u64 * period;
+
+        for Reading { red, ir } in readings {
+            self.monitor.update(ir, red, self.clock_ms);
+            self.clock_ms += period;
         }
-    });
+
+        let Reading { red, ir } = last;
+
+        let bpm = self.monitor.get_bpm().round().clamp(0.0, MAX_BPM) as u32;
+        info!("BPM: {}, red: {}, ir: {}", bpm, red, ir);
+
+        Ok(Sample {
+            heart_rate: self.monitor.finger_detected.then_some(bpm),
+            bpm,
+            hrv: self.monitor.get_hrv(),
+        })
+    }
+}
+
+pub fn max3010x<I2C>(i2c: I2C, solver: Arc<Solver>) -> Result<()>
+where
+    I2C: Write + Read + Send + Sync + Clone + 'static,
+    <I2C as Write>::Error: Error + Send + Sync + Sized + 'static,
+    <I2C as Read>::Error: Error + Send + Sync + Sized + 'static,
+{
+    let pulse = Pulse {
+        max3010x: Sensor::new(i2c.clone(), &Config::default())?,
+        i2c,
+        monitor: HeartRateMonitor::new(),
+        clock_ms: 0,
+    };
+
+    Scheduler::new(pulse, |c| c.max3010x.sampling.tick())
+        .display(|_| 1, |sample, vitals| vitals.heart_rate = sample.heart_rate)
+        .live(|c| c.max3010x.sampling.live_every(), |sample| {
+            vec![Max3010x {
+                heart_rate: sample.bpm,
+            }]
+        })
+        .persisted(|c| c.max3010x.sampling.persist_every(), |sample| {
+            vec![Max3010x {
+                heart_rate: sample.bpm,
+            }]
+        })
+        .sink(|c| c.max3010x.hrv_every(), |sample, solver| {
+            if let Some(metrics) = sample.hrv {
+                info!("HRV => {:?}", metrics);
+                let _ = solver.send_to_socket(Message::new(Hrv::from(metrics)));
+                let _ = solver.send_to_database(Message::new(Hrv::from(metrics)));
+            }
+        })
+        .spawn(solver);
 
     Ok(())
-}
\ No newline at end of file
+}
@@ -1,91 +1,169 @@
 use crate::{
-    client::Client,
+    client::{self, Client},
+    commands,
     network::Network,
     solver::Solver,
-    utils::driver::{ArcDriver, PinAsync},
+    utils::{
+        config,
+        driver::{ArcDriver, PinAsync},
+        gesture::Gesture,
+        logger,
+        supervisor::{self, Restart, Task},
+    },
 };
 use anyhow::Result;
 use esp_idf_svc::hal::{
+    adc::ADC1,
     gpio::{AnyIOPin, PinDriver, Pins},
     i2c::{config::Config, I2cDriver, I2C0},
     prelude::Hertz,
 };
 use std::{
     sync::{Arc, Mutex},
-    thread,
     time::Duration,
 };
 
+pub mod alarm;
 pub mod button;
 pub mod ds18b20;
+pub mod haptics;
 pub mod max3010x;
 pub mod mpu6050;
+pub mod sos;
 pub mod ssd1306;
+pub mod telemetry;
 
+pub use alarm::alarm;
 pub use button::button;
 pub use ds18b20::ds18b20;
+pub use haptics::haptics;
 pub use max3010x::max3010x;
 pub use mpu6050::mpu6050;
 pub use ssd1306::ssd1306;
+pub use telemetry::telemetry;
+
+const WATCHDOG: Duration = Duration::from_secs(30);
 
 macro_rules! i2c_threads {
-    ($arr:expr, $driver:expr, $solver:expr) => {
-        for i in $arr.iter() {
+    ([$($handler:ident),*], $driver:expr, $solver:expr) => {
+        $(
             let d = $driver.clone();
             let s = $solver.clone();
-            thread::spawn(move || {
-                while let Err(e) = i(d.clone(), Arc::clone(&s)) {
-                    println!("Error: {:?}", e);
-                    thread::sleep(Duration::from_secs(5));
-                }
-            });
-        }
+            Task::new(stringify!($handler)).spawn(move || $handler(d.clone(), Arc::clone(&s)));
+        )*
     };
 }
 
 pub fn pin_threads(
-    arr: Vec<(fn(AnyIOPin, Arc<Solver>) -> Result<()>, PinAsync)>,
+    arr: Vec<(
+        &'static str,
+        fn(AnyIOPin, Arc<Solver>) -> Result<()>,
+        PinAsync,
+    )>,
     solver: Arc<Solver>,
 ) {
-    for elm in arr {
+    for (name, handler, pin) in arr {
         let s = Arc::clone(&solver);
-        let pin = elm.1.clone();
-        thread::spawn(move || {
-            while let Err(e) = elm.0(pin.clone().pin(), Arc::clone(&s)) {
-                println!("Error: {:?}", e);
-                thread::sleep(Duration::from_secs(5));
-            }
-        });
+        Task::new(name)
+            .watchdog(WATCHDOG)
+            .spawn(move || handler(pin.clone().pin(), Arc::clone(&s)));
+    }
+}
+
+fn cancel(solver: Arc<Solver>) -> impl Fn() -> Result<bool> + Send + Sync {
+    move || {
+        let cancelled = solver.countdown.cancel();
+
+        if cancelled {
+            haptics::stop();
+        }
+
+        Ok(cancelled)
     }
 }
 
 pub fn init(
     pins: Pins,
     i2c0: I2C0,
+    adc1: ADC1,
     network: Arc<Network>,
     client: Arc<Mutex<Option<Client>>>,
 ) -> Result<()> {
     let config = Config::new().baudrate(Hertz(400_000));
     let ds18b20_pin = PinAsync(pins.gpio8.into());
     let button_pin = PinAsync(pins.gpio3.into());
+    let vibrator_pin = PinAsync(pins.gpio21.into());
     let i2c = I2cDriver::new(i2c0, pins.gpio6, pins.gpio7, &config)?;
     let driver = ArcDriver::new(i2c);
     let solver = Arc::new(Solver::new(client, network)?);
+    solver.spawn_flush();
+    solver.spawn_history();
+
+    let s = solver.clone();
+    commands::register(config::CONFIG, move |data| {
+        let config = config::apply(data)?;
+        log::info!("Config updated: {:?}", config);
 
-    let vibrator_pin = pins.gpio21;
-    let mut vibrator = PinDriver::input_output(vibrator_pin)?;
-    vibrator.set_low()?;
+        if let Ok(client) = s.client.lock() {
+            if let Some(client) = client.as_ref() {
+                client::report_config(client)?;
+            }
+        }
+
+        Ok(())
+    });
+
+    // A press stops a running countdown before it moves the screen.
+    button::subscribe(Gesture::Short, cancel(solver.clone()));
+    button::subscribe(Gesture::Double, cancel(solver.clone()));
+    let s = solver.clone();
+    button::subscribe(Gesture::Short, move || {
+        s.screens.next();
+        Ok(true)
+    });
+    let s = solver.clone();
+    button::subscribe(Gesture::Double, move || {
+        s.screens.home();
+        Ok(true)
+    });
+    let s = solver.clone();
+    button::subscribe(Gesture::Long, move || {
+        sos::start(&s);
+        Ok(true)
+    });
 
     i2c_threads!([max3010x, mpu6050], driver.clone(), solver.clone());
+
+    if let Err(e) = telemetry(adc1, pins.gpio4, solver.clone()) {
+        log::warn!("Telemetry unavailable: {:?}", e);
+    }
+
     pin_threads(
-        vec![(ds18b20, ds18b20_pin), (button, button_pin)],
+        vec![
+            ("ds18b20", ds18b20, ds18b20_pin),
+            ("button", button, button_pin),
+            ("haptics", haptics, vibrator_pin),
+        ],
         solver.clone(),
     );
 
-    while let Err(e) = ssd1306(driver.clone()) {
-        println!("Error: {:?}", e);
-        thread::sleep(Duration::from_secs(5));
-    }
+    let s = solver.clone();
+    Task::new("alarm")
+        .watchdog(WATCHDOG)
+        .spawn(move || alarm(s.clone()));
+
+    commands::register(haptics::HAPTICS, |data| {
+        haptics::play(serde_json::from_str::<haptics::Command>(data)?.pattern);
+        Ok(())
+    });
+
+    let s = solver.clone();
+    Task::new("ssd1306")
+        .restart(Restart::Always)
+        .watchdog(WATCHDOG)
+        .spawn(move || ssd1306(driver.clone(), s.clone()));
 
-    Ok(())
+    logger::forward(solver.client.clone());
+    supervisor::report(solver);
+    supervisor::monitor()
 }
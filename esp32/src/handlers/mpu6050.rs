@@ -1,20 +1,32 @@
 use crate::{
-    drivers::mpu6050::Mpu6050 as Sensor,
+    analysis::{
+        activity::{self, ActivityClassifier, Profile},
+        fall::{FallDetector, Vector},
+    },
+    drivers::mpu6050::{AccelRange, Config, Mpu6050 as Sensor},
     solver::{Message, Solver},
-    utils::check,
+    utils::{
+        config,
+        scheduler::{self, Scheduler},
+    },
 };
 use anyhow::Result;
 use embedded_hal::blocking::i2c::{Write, WriteRead};
 use log::info;
 use serde::{Deserialize, Serialize};
-use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 use std::{
     error::Error,
-    sync::{Arc, Mutex},
-    thread,
-    time::Duration,
+    sync::Arc,
+    time::{Duration, Instant},
 };
 
+const TICK_MS: u32 = 20;
+const TICK: Duration = Duration::from_millis(TICK_MS as u64);
+const ACTIVITY_WINDOW: f32 = 10.0;
+// Impacts peak well above 2 g, so the default range would clip them below
+// the fall threshold.
+const ACCEL_RANGE: AccelRange = AccelRange::G8;
+
 #[derive(Serialize, Deserialize)]
 pub struct Accel {
     pub x: i16,
@@ -22,6 +34,13 @@ pub struct Accel {
     pub z: i16,
 }
 
+#[derive(Serialize, Deserialize)]
+pub struct Gyro {
+    pub x: i16,
+    pub y: i16,
+    pub z: i16,
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct Rotation {
     pub yaw: f32,
@@ -34,87 +53,145 @@ pub struct Mpu6050 {
     pub steps: u32,
 }
 
+#[derive(Serialize, Deserialize)]
+pub struct Activity {
+    pub activity: String,
+    pub intensity: f32,
+    pub calories: f32,
+    pub duration: u32,
+}
+
+struct Motion<I2C>
+where
+    I2C: WriteRead + Write + Send + Sync + Clone + 'static,
+    <I2C as WriteRead>::Error: Error + Send + Sync + Sized + 'static,
+    <I2C as Write>::Error: Error + Send + Sync + Sized + 'static,
+{
+    i2c: I2C,
+    mpu6050: Sensor<I2C>,
+}
+
+struct Sample {
+    accel: Vector,
+    rotation: Option<Rotation>,
+}
+
+impl<I2C> scheduler::Sensor for Motion<I2C>
+where
+    I2C: WriteRead + Write + Send + Sync + Clone + 'static,
+    <I2C as WriteRead>::Error: Error + Send + Sync + Sized + 'static,
+    <I2C as Write>::Error: Error + Send + Sync + Sized + 'static,
+{
+    type Sample = Sample;
+
+    fn name(&self) -> &'static str {
+        "mpu6050"
+    }
+
+    fn init(&mut self) -> Result<()> {
+        self.mpu6050 = Sensor::with_config(self.i2c.clone(), config())?;
+        Ok(())
+    }
+
+    fn sample(&mut self) -> Result<Sample> {
+        Ok(Sample {
+            accel: self.mpu6050.get_accel_g()?,
+            rotation: self.mpu6050.get_rotation().ok(),
+        })
+    }
+}
+
+fn config() -> Config {
+    Config {
+        accel: ACCEL_RANGE,
+        ..Config::default()
+    }
+}
+
 pub fn mpu6050<I2C>(i2c: I2C, solver: Arc<Solver>) -> Result<()>
 where
     I2C: WriteRead + Write + Send + Sync + Clone + 'static,
     <I2C as WriteRead>::Error: Error + Send + Sync + Sized + 'static,
     <I2C as Write>::Error: Error + Send + Sync + Sized + 'static,
 {
-    let mpu6050 = Arc::new(Mutex::new(Sensor::new(i2c.clone())?));
-    let steps = Arc::new(AtomicU32::new(0));
-
-    let m = mpu6050.clone();
-    let s = solver.clone();
-    let st = steps.clone();
-    thread::spawn(move || {
-        let check = check::Check::new(20);
-        let mpu6050 = Arc::clone(&m);
-        let solver = Arc::clone(&s);
-        let mut accel;
-        let mut rotation;
-        let steps = Arc::clone(&st);
-        // let mut steps: u32 = 0;
-        // let mut accx;
-
-        loop {
-            if let Ok(mut mpu6050) = mpu6050.lock() {
-                accel = mpu6050.get_accel();
-                rotation = mpu6050.get_rotation();
-
-                if let Ok((accel, rotation)) = accel.and_then(|a| rotation.map(|r| (a, r))) {
-                    // steps += 1;
-                    // info!("steps => {}", steps);
-                    info!("steps => {}", steps.fetch_add(1, Ordering::Relaxed));
-                    info!("SOCKET => accel: {:?}, rotation: {:?}", accel, rotation);
-
-                    let _ = solver.send_to_socket(Message::new(Mpu6050 {
-                        steps: steps.load(Ordering::Relaxed),
-                    }));
-                } else {
-                    info!("Error reading sensor");
-                    check.error();
-
-                    if check.is_limit() {
-                        thread::sleep(Duration::from_secs(5));
-                        if let Ok(sensor) = Sensor::new(i2c.clone()) {
-                            *mpu6050 = sensor;
-                        } else {
-                            info!("Error creating sensor");
-                        }
-                    }
+    let motion = Motion {
+        mpu6050: Sensor::with_config(i2c.clone(), config())?,
+        i2c,
+    };
+
+    let mut detector = FallDetector::new();
+    let start = Instant::now();
+
+    let mut classifier = ActivityClassifier::new(1.0 / TICK.as_secs_f32(), ACTIVITY_WINDOW);
+    let steps = |solver: &Solver| Mpu6050 {
+        steps: solver.vitals.lock().map_or(0, |vitals| vitals.steps),
+    };
+
+    Scheduler::new(motion, |_| TICK)
+        .sink(
+            |_| 1,
+            move |sample, solver| {
+                if detector.update(sample.accel, start.elapsed()) {
+                    info!("Fall detected");
+                    solver
+                        .countdown
+                        .start("fall", config::get().mpu6050.fall_countdown());
                 }
-            }
-
-            thread::sleep(Duration::from_secs(5));
-        }
-    });
-
-    let m = mpu6050.clone();
-    let s = solver.clone();
-    thread::spawn(move || {
-        let mpu6050 = Arc::clone(&m);
-        let solver = Arc::clone(&s);
-        let mut accel;
-        let mut rotation;
-
-        loop {
-            if let Ok(mut mpu6050) = mpu6050.lock() {
-                accel = mpu6050.get_accel();
-                rotation = mpu6050.get_rotation();
-
-                if let Ok((accel, rotation)) = accel.and_then(|a| rotation.map(|r| (a, r))) {
-                    info!("DATABASE => accel: {:?}, rotation: {:?}", accel, rotation);
-                    let _ = solver.send_to_database(Message::new(Mpu6050 {
-                        steps: steps.load(Ordering::Relaxed),
-                    }));
-                } else {
-                    info!("Error reading sensor");
+            },
+        )
+        .sink(
+            |_| 1,
+            move |sample, solver| {
+                let Some(window) = classifier.update(sample.accel.magnitude()) else {
+                    return;
+                };
+
+                let wearer = config::get().wearer;
+                let profile = Profile {
+                    weight: wearer.weight,
+                    age: wearer.age,
+                    sex: wearer.sex,
+                };
+                let calories = activity::calories(&window, solver.heart_rate(), &profile);
+                info!("activity => {:?}, calories: {}", window, calories);
+
+                if let Ok(mut vitals) = solver.vitals.lock() {
+                    vitals.activity = Some(window.kind.as_str());
+                }
+
+                let activity = || Activity {
+                    activity: window.kind.as_str().to_string(),
+                    intensity: window.intensity,
+                    calories,
+                    duration: window.seconds.round() as u32,
+                };
+
+                let _ = solver.send_to_socket(Message::new(activity()));
+                let _ = solver.send_to_database(Message::new(activity()));
+            },
+        )
+        .sink(
+            |c| config::every(c.mpu6050.live_ms, TICK_MS),
+            move |sample, solver| {
+                if let Ok(mut vitals) = solver.vitals.lock() {
+                    info!("steps => {}", vitals.steps);
+                    vitals.steps += 1;
                 }
-            }
 
-            thread::sleep(Duration::from_secs(3));
-        }
-    });
+                info!(
+                    "SOCKET => accel: {:?}, rotation: {:?}",
+                    sample.accel, sample.rotation
+                );
+                let _ = solver.send_to_socket(Message::new(steps(solver)));
+            },
+        )
+        .sink(
+            |c| config::every(c.mpu6050.persist_ms, TICK_MS),
+            move |_, solver| {
+                let _ = solver.send_to_database(Message::new(steps(solver)));
+            },
+        )
+        .spawn(solver);
 
     Ok(())
 }
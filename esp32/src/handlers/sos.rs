@@ -0,0 +1,99 @@
+use crate::{
+    display::Alert,
+    handlers::{
+        button::{Priority, Report},
+        haptics,
+    },
+    solver::{Message, Solver},
+    utils::{
+        haptics::Pattern,
+        supervisor::{Restart, Task},
+        time,
+    },
+};
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+pub const SOS: &str = "sos";
+
+const COUNTDOWN: Duration = Duration::from_secs(10);
+// How long the display confirms a delivered report.
+const DELIVERED_SHOWN: Duration = Duration::from_secs(30);
+const WATCHDOG: Duration = Duration::from_secs(30);
+
+#[derive(Clone, Copy, Debug)]
+enum Delivery {
+    Sending { attempt: u32 },
+    Delivered { at: Instant },
+}
+
+static DELIVERY: Mutex<Option<Delivery>> = Mutex::new(None);
+
+fn set(delivery: Delivery) {
+    if let Ok(mut current) = DELIVERY.lock() {
+        *current = Some(delivery);
+    }
+}
+
+/// Starts the countdown the wearer can still cancel with a press.
+pub fn start(solver: &Solver) -> bool {
+    let started = solver.countdown.start(SOS, COUNTDOWN);
+
+    if started {
+        log::warn!("SOS requested, sending in {}s", COUNTDOWN.as_secs());
+    }
+
+    started
+}
+
+/// What the display shows about the last SOS report.
+pub fn alert() -> Option<Alert> {
+    match (*DELIVERY.lock().ok()?)? {
+        Delivery::Sending { attempt } => Some(Alert::Sending { attempt }),
+        Delivery::Delivered { at } if at.elapsed() < DELIVERED_SHOWN => Some(Alert::Delivered),
+        Delivery::Delivered { .. } => None,
+    }
+}
+
+/// Sends a high-priority report with the latest vitals, retrying until the
+/// broker confirms it.
+pub fn send(solver: Arc<Solver>) {
+    let headers = time::stamp();
+    let report = Report {
+        status: SOS.to_string(),
+        description: "SOS requested by the wearer".to_string(),
+        priority: Priority::High,
+        vitals: Some(
+            solver
+                .vitals
+                .lock()
+                .map(|vitals| *vitals)
+                .unwrap_or_default(),
+        ),
+    };
+
+    log::error!("SOS countdown expired, sending report");
+    set(Delivery::Sending { attempt: 0 });
+
+    if let Err(e) = solver.send_to_socket(Message::new(report.clone())) {
+        log::warn!("Failed to send SOS to socket: {:?}", e);
+    }
+
+    Task::new("sos")
+        .restart(Restart::OnError)
+        .watchdog(WATCHDOG)
+        .spawn(move || {
+            let message = Message {
+                headers,
+                payload: report.clone().into(),
+            };
+
+            solver.deliver(message, |attempt| set(Delivery::Sending { attempt }))?;
+            set(Delivery::Delivered { at: Instant::now() });
+            haptics::play(Pattern::Heartbeat);
+            log::info!("SOS delivered");
+            Ok(())
+        });
+}
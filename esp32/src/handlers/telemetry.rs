@@ -0,0 +1,99 @@
+use crate::{
+    solver::Solver,
+    utils::{
+        battery,
+        scheduler::{self, Scheduler},
+        settings, status, time,
+    },
+};
+use anyhow::Result;
+use esp_idf_svc::hal::{
+    adc::{self, attenuation, AdcChannelDriver, AdcDriver, ADC1},
+    gpio::Gpio4,
+};
+use esp_idf_sys::{esp_get_free_heap_size, esp_get_minimum_free_heap_size};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, sync::Arc, time::Duration};
+
+const TICK: Duration = Duration::from_secs(60);
+// The cell is read through a divider that halves its voltage.
+const DIVIDER: u32 = 2;
+const SAMPLES: u32 = 8;
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Telemetry {
+    // Telemetry shares one topic, so it names the device it came from.
+    pub device: String,
+    pub voltage: Option<f32>,
+    pub battery: Option<u8>,
+    pub rssi: Option<i8>,
+    pub uptime: u64,
+    pub heap: u32,
+    pub min_heap: u32,
+    pub queue: usize,
+    pub errors: BTreeMap<String, u32>,
+}
+
+struct Device {
+    adc: AdcDriver<'static, ADC1>,
+    channel: AdcChannelDriver<'static, { attenuation::DB_11 }, Gpio4>,
+    solver: Arc<Solver>,
+}
+
+impl Device {
+    fn millivolts(&mut self) -> Result<u32> {
+        let mut total = 0;
+
+        for _ in 0..SAMPLES {
+            total += self.adc.read(&mut self.channel)? as u32;
+        }
+
+        Ok(total / SAMPLES * DIVIDER)
+    }
+}
+
+impl scheduler::Sensor for Device {
+    type Sample = Telemetry;
+
+    fn name(&self) -> &'static str {
+        "telemetry"
+    }
+
+    fn init(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    fn sample(&mut self) -> Result<Telemetry> {
+        let millivolts = self.millivolts().ok();
+        let battery = millivolts.map(battery::percentage);
+
+        status::update(|status| status.battery = battery);
+
+        Ok(Telemetry {
+            device: settings::get().device.clone(),
+            voltage: millivolts.map(|mv| mv as f32 / 1000.0),
+            battery,
+            rssi: self.solver.network.rssi(),
+            uptime: time::uptime_ms() / 1000,
+            heap: unsafe { esp_get_free_heap_size() },
+            min_heap: unsafe { esp_get_minimum_free_heap_size() },
+            queue: self.solver.queue_len(),
+            errors: scheduler::errors(),
+        })
+    }
+}
+
+pub fn telemetry(adc1: ADC1, pin: Gpio4, solver: Arc<Solver>) -> Result<()> {
+    let device = Device {
+        adc: AdcDriver::new(adc1, &adc::config::Config::new().calibration(true))?,
+        channel: AdcChannelDriver::new(pin)?,
+        solver: solver.clone(),
+    };
+
+    Scheduler::new(device, |_| TICK)
+        .live(|_| 1, |sample| vec![sample.clone()])
+        .persisted(|_| 1, |sample| vec![sample.clone()])
+        .spawn(solver);
+
+    Ok(())
+}
@@ -1,9 +0,0 @@
-pub const BATERRY: [u8; 30] = [
-    0xff, 0xff, 0x00, 0xc0, 0x01, 0x00, 0xb2, 0x6d, 0xc0, 0xb7, 0x6d, 0xc0, 0xb7, 0x6d, 0xc0, 0xb7,
-    0x6d, 0xc0, 0xb7, 0x6d, 0xc0, 0xb2, 0x6d, 0xc0, 0xc0, 0x01, 0x00, 0xff, 0xff, 0x00,
-];
-
-pub const WIFI: [u8; 20] = [
-    0x3f, 0xe0, 0xff, 0xf8, 0xff, 0xf8, 0x7f, 0xf0, 0x7f, 0xe0, 0x3f, 0xe0, 0x1f, 0xc0, 0x0f, 0x80,
-    0x07, 0x00, 0x02, 0x00,
-];
\ No newline at end of file
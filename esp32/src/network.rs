@@ -1,3 +1,8 @@
+use crate::utils::{
+    connection::Backoff,
+    status,
+    supervisor::{self, Restart, Task},
+};
 use anyhow::{bail, Result};
 use esp_idf_svc::{
     hal::{modem::Modem, peripheral::Peripheral},
@@ -9,7 +14,20 @@ use log::info;
 use std::{sync::Mutex, thread, time::Duration};
 
 static mut WIFI: Option<Mutex<EspWifi<'static>>> = None;
-const MAX_RETRIES: u8 = 7;
+const RETRY_MIN: Duration = Duration::from_secs(2);
+const RETRY_MAX: Duration = Duration::from_secs(300);
+const POLL: Duration = Duration::from_secs(5);
+const WATCHDOG: Duration = Duration::from_secs(30);
+
+fn backoff() -> Backoff {
+    Backoff::new(RETRY_MIN, RETRY_MAX, unsafe { esp_idf_sys::esp_random() })
+}
+
+fn ap_rssi() -> Option<i8> {
+    let mut info = esp_idf_sys::wifi_ap_record_t::default();
+    esp_idf_sys::esp!(unsafe { esp_idf_sys::esp_wifi_sta_get_ap_info(&mut info) }).ok()?;
+    Some(info.rssi)
+}
 
 pub struct Network {
     pub sysloop: EspSystemEventLoop,
@@ -100,27 +118,20 @@ impl Network {
                 }
 
                 info!("Connecting wifi...");
-                let mut count = 0;
+                let mut backoff = backoff();
 
                 loop {
                     info!("Waiting for wifi connection...");
 
                     {
                         if let Ok(mut wifi) = wifi.lock() {
-                            if wifi.connect().is_err() {
-                                std::thread::sleep(std::time::Duration::from_secs(10));
+                            if let Err(e) = wifi.connect() {
+                                info!("Wifi connect error: {:?}", e);
                             }
                         }
                     }
 
-                    if count >= MAX_RETRIES - 1 {
-                        esp_idf_sys::esp_restart();
-                        // esp_idf_sys::nvs_flash_erase();
-                        // esp_idf_sys::nvs_flash_deinit();
-                        // count = 0;
-                    }
-
-                    count += 1;
+                    thread::sleep(backoff.next_delay());
 
                     if let Ok(wifi) = wifi.lock() {
                         if wifi.is_connected()? {
@@ -144,27 +155,54 @@ impl Network {
         Ok(())
     }
 
-    pub fn listen<F>(&self, services: F) -> Result<()>
+    /// Reconnects the station with backoff, calling `on_change` whenever the
+    /// link goes up or down.
+    pub fn listen<F>(&self, on_change: F) -> Result<()>
     where
-        F: Fn() + Send + 'static,
+        F: Fn(bool) + Send + 'static,
     {
-        thread::spawn(move || loop {
-            if let Some(wifi) = unsafe { WIFI.take() } {
-                if let Ok(mut wifi) = wifi.lock() {
-                    if !wifi.is_connected().unwrap_or(false) {
-                        info!("Reconnecting...");
-                        let _ = wifi.connect();
-                        services();
+        Task::new("wifi")
+            .restart(Restart::Always)
+            .watchdog(WATCHDOG)
+            .spawn(move || {
+                let mut backoff = backoff();
+                let mut connected = true;
+
+                loop {
+                    let mut delay = POLL;
+
+                    if let Some(wifi) = unsafe { WIFI.take() } {
+                        if let Ok(mut wifi) = wifi.lock() {
+                            let current = wifi.is_connected().unwrap_or(false);
+
+                            if current != connected {
+                                connected = current;
+                                on_change(current);
+                            }
+
+                            status::update(|status| {
+                                status.wifi = current;
+                                status.rssi = if current { ap_rssi() } else { None };
+                            });
+
+                            if current {
+                                backoff.reset();
+                            } else {
+                                info!("Reconnecting...");
+                                let _ = wifi.connect();
+                                delay = backoff.next_delay();
+                            }
+                        }
+
+                        unsafe {
+                            WIFI = Some(wifi);
+                        }
                     }
-                }
 
-                unsafe {
-                    WIFI = Some(wifi);
+                    supervisor::beat_for(delay);
+                    thread::sleep(delay);
                 }
-            }
-
-            thread::sleep(Duration::from_secs(5));
-        });
+            });
 
         Ok(())
     }
@@ -187,4 +225,9 @@ impl Network {
             }
         }
     }
+
+    /// Signal strength of the access point, while connected.
+    pub fn rssi(&self) -> Option<i8> {
+        ap_rssi()
+    }
 }
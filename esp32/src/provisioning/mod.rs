@@ -0,0 +1,136 @@
+use crate::{utils::settings, HOSTPOT_PASSWORD, HOSTPOT_SSID};
+use anyhow::Result;
+use embedded_svc::{
+    http::Method,
+    io::{Read, Write},
+};
+use esp_idf_svc::{
+    eventloop::EspSystemEventLoop,
+    hal::modem::Modem,
+    http::server::{Configuration as HttpConfiguration, EspHttpServer},
+    nvs::EspDefaultNvsPartition,
+    wifi::{AccessPointConfiguration, AuthMethod, Configuration, EspWifi},
+};
+use log::info;
+use std::{
+    sync::{Arc, Mutex},
+    thread,
+    time::{Duration, Instant},
+};
+
+pub use common::provisioning::form;
+
+const MAX_BODY: usize = 1024;
+const RESTART_DELAY: Duration = Duration::from_secs(2);
+const SAVED: &str = "Saved, restarting...";
+// How long the portal waits for a request before giving up on a device that
+// already has settings.
+const PORTAL_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+const POLL: Duration = Duration::from_secs(5);
+
+fn restart() -> ! {
+    unsafe { esp_idf_sys::esp_restart() }
+}
+
+/// Brings up the setup access point and serves the settings form until the
+/// device is configured, then reboots into normal operation. A device that
+/// is `provisioned` also reboots once the portal sits unused.
+pub fn run(
+    modem: Modem,
+    sysloop: EspSystemEventLoop,
+    nvs: EspDefaultNvsPartition,
+    provisioned: bool,
+) -> Result<()> {
+    let mut wifi = EspWifi::new(modem, sysloop, Some(nvs))?;
+
+    wifi.set_configuration(&Configuration::AccessPoint(AccessPointConfiguration {
+        ssid: HOSTPOT_SSID.into(),
+        password: HOSTPOT_PASSWORD.into(),
+        auth_method: AuthMethod::WPA2Personal,
+        ..Default::default()
+    }))?;
+    wifi.start()?;
+
+    info!(
+        "Provisioning on {} at {:?}",
+        HOSTPOT_SSID,
+        wifi.ap_netif().get_ip_info()?
+    );
+
+    let mut server = EspHttpServer::new(&HttpConfiguration::default())?;
+    let last_request = Arc::new(Mutex::new(Instant::now()));
+
+    let last = last_request.clone();
+    server.fn_handler("/", Method::Get, move |req| {
+        if let Ok(mut last) = last.lock() {
+            *last = Instant::now();
+        }
+
+        let page = form::render(settings::get(), None);
+        req.into_ok_response()?.write_all(page.as_bytes())?;
+        Ok(())
+    })?;
+
+    let last = last_request.clone();
+    server.fn_handler("/", Method::Post, move |mut req| {
+        if let Ok(mut last) = last.lock() {
+            *last = Instant::now();
+        }
+
+        let mut body = [0u8; MAX_BODY];
+        let mut len = 0;
+
+        while len < body.len() {
+            let read = req.read(&mut body[len..])?;
+
+            if read == 0 {
+                break;
+            }
+
+            len += read;
+        }
+
+        let body = std::str::from_utf8(&body[..len]).unwrap_or_default();
+        let saved = form::parse(body).and_then(|current| {
+            settings::save(&current)?;
+            Ok(current)
+        });
+
+        match saved {
+            Ok(current) => {
+                info!(
+                    "Provisioned {} on {}:{} as {}",
+                    current.ssid, current.host, current.port, current.device
+                );
+
+                let page = form::render(&current, Some(SAVED));
+                req.into_ok_response()?.write_all(page.as_bytes())?;
+
+                thread::spawn(|| {
+                    thread::sleep(RESTART_DELAY);
+                    restart();
+                });
+            }
+            Err(e) => {
+                let page = form::render(settings::get(), Some(&e.to_string()));
+                req.into_status_response(400)?.write_all(page.as_bytes())?;
+            }
+        }
+
+        Ok(())
+    })?;
+
+    // The access point and the server stop when dropped.
+    loop {
+        thread::sleep(POLL);
+
+        let idle = last_request
+            .lock()
+            .map_or(Duration::ZERO, |last| last.elapsed());
+
+        if provisioned && idle >= PORTAL_TIMEOUT {
+            info!("Provisioning portal unused, restarting");
+            restart();
+        }
+    }
+}
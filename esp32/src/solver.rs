@@ -1,17 +1,53 @@
 use crate::{
-    client::Client,
-    handlers::{ds18b20::Ds18b20, max3010x::Max3010x, mpu6050::Mpu6050, button::Report},
+    client::{self, Client},
+    display::Screens,
+    handlers::{
+        button::Report,
+        ds18b20::Ds18b20,
+        max3010x::{Hrv, Max3010x},
+        mpu6050::{Activity, Mpu6050},
+        telemetry::Telemetry,
+    },
     network::Network,
+    storage::{Entry, Flash, Memory, Policy, Ring, Storage},
+    utils::{
+        clock::Stamp,
+        connection::Backoff,
+        countdown::Countdown,
+        history::History,
+        status,
+        supervisor::{self, Health, Restart, Task},
+        time,
+        vitals::Vitals,
+    },
 };
-use anyhow::Result;
+use anyhow::{anyhow, Result};
+use log::{info, warn};
 use serde::{Deserialize, Serialize};
-use std::sync::{Arc, Mutex};
+use std::{
+    sync::{Arc, Mutex},
+    thread,
+    time::{Duration, Instant},
+};
 
 pub const SOCKET: &str = "socket";
 pub const DATABASE: &str = "database";
 pub const RED_UPDATES: [&str; 2] = [SOCKET, DATABASE];
 
-const LIMIT: usize = 3000;
+const CAPACITY: usize = 3000;
+// Without flash the queue lives on the heap, which has room for far fewer.
+const MEMORY_CAPACITY: usize = 128;
+const POLICY: Policy = Policy::Downsample;
+const FLUSH_BATCH: usize = 16;
+const FLUSH_PERIOD: Duration = Duration::from_millis(500);
+const FLUSH_IDLE: Duration = Duration::from_secs(5);
+const ACK_TIMEOUT: Duration = Duration::from_secs(5);
+const ACK_POLL: Duration = Duration::from_millis(50);
+const FLUSH_WATCHDOG: Duration = Duration::from_secs(30);
+const DELIVER_RETRY_MIN: Duration = Duration::from_secs(2);
+const DELIVER_RETRY_MAX: Duration = Duration::from_secs(30);
+const HISTORY_PERIOD: Duration = Duration::from_secs(10);
+const HISTORY_WATCHDOG: Duration = Duration::from_secs(30);
 
 macro_rules! count_idents {
     ($($idents:ident),*) => {
@@ -60,88 +96,287 @@ macro_rules! set_payloads {
     }
 }
 
-#[derive(Serialize, Deserialize)]
-pub struct Headers {
-    pub timestamp: i64,
-}
-
 #[derive(Serialize, Deserialize)]
 pub struct Message {
-    pub headers: Headers,
+    pub headers: Stamp,
     pub payload: Payload,
 }
 
 pub struct Solver {
     pub client: Arc<Mutex<Option<Client>>>,
     pub network: Arc<Network>,
-    pub storage: Mutex<Vec<(String, Message)>>,
+    pub storage: Mutex<Box<dyn Storage>>,
+    pub countdown: Countdown,
+    pub vitals: Mutex<Vitals>,
+    pub history: Mutex<History>,
+    pub screens: Screens,
 }
 
 unsafe impl Send for Solver {}
 unsafe impl Sync for Solver {}
 
-set_payloads!(Ds18b20, Max3010x, Mpu6050, Report);
+set_payloads!(Ds18b20, Max3010x, Mpu6050, Report, Activity, Hrv, Health, Telemetry);
 
 impl Message {
     pub fn new<P: Into<Payload>>(payload: P) -> Self {
         Self {
-            headers: Headers {
-                timestamp: chrono::Local::now().timestamp(),
-            },
+            headers: time::stamp(),
             payload: payload.into(),
         }
     }
+
+    // Messages stamped before the clock synced get their wall time on the
+    // way out.
+    fn into_json(mut self) -> Result<String> {
+        self.headers = time::resolve(self.headers);
+        Ok(serde_json::to_string(&self)?)
+    }
+}
+
+// Reports are few and each one matters, unlike a reading among many.
+fn is_report(entry: &Entry) -> bool {
+    entry.route == format!("{}/{}", DATABASE, "report")
 }
 
 impl Solver {
     pub fn new(client: Arc<Mutex<Option<Client>>>, network: Arc<Network>) -> Result<Self> {
+        let storage: Box<dyn Storage> =
+            match Flash::new().and_then(|flash| Ring::new(flash, CAPACITY, POLICY)) {
+                Ok(ring) => Box::new(ring.keep(is_report)),
+                Err(e) => {
+                    info!("Flash queue unavailable, buffering in memory: {:?}", e);
+                    Box::new(
+                        Ring::new(Memory::new(MEMORY_CAPACITY), MEMORY_CAPACITY, POLICY)?
+                            .keep(is_report),
+                    )
+                }
+            };
+
+        status::update(|status| status.queue = storage.len());
+
         Ok(Self {
             client,
-            storage: Mutex::new(Vec::new()),
+            storage: Mutex::new(storage),
+            countdown: Countdown::new(),
+            vitals: Mutex::new(Vitals::default()),
+            history: Mutex::new(History::new()),
+            screens: Screens::new(),
             network,
         })
     }
 
+    pub fn queue_len(&self) -> usize {
+        self.storage.lock().map_or(0, |storage| storage.len())
+    }
+
+    pub fn heart_rate(&self) -> Option<u32> {
+        self.vitals.lock().ok().and_then(|vitals| vitals.heart_rate)
+    }
+
     pub fn send_to_database(&self, message: Message) -> Result<()> {
         let route = format!("{}/{}", DATABASE, message.payload.get_topic());
-        if let Ok(mut client_opt) = self.client.lock() {
-            if let Some(client) = client_opt.as_mut() {
-                // if self.network.is_connected()? {
-                let message = serde_json::to_string(&message)?;
-
-                if let Ok(mut storage) = self.storage.lock() {
-                    for (_route, mut _message) in storage.drain(..) {
-                        let now = chrono::Local::now().timestamp() as i64;
-                        _message.headers.timestamp = (now - _message.headers.timestamp) + now;
-
-                        let _message = serde_json::to_string(&_message)?;
-                        client.publish(&_route, &_message)?;
+        let message = message.into_json()?;
+
+        // Readings taken before the clock is synced wait in the queue, where
+        // they get their wall time before being flushed.
+        if let Ok(client) = self.client.lock() {
+            if let Some(client) = client
+                .as_ref()
+                .filter(|_| client::is_connected() && time::is_synced())
+            {
+                match client.publish_reliable(&route, &message) {
+                    Ok(_) => return Ok(()),
+                    Err(e) => info!("Failed to publish {}, queueing: {:?}", route, e),
+                }
+            }
+        }
+
+        if let Ok(mut storage) = self.storage.lock() {
+            storage.push(Entry { route, message })?;
+            status::update(|status| status.queue = storage.len());
+        }
+
+        Ok(())
+    }
+
+    /// Drains the queue in batches, removing entries only once the broker
+    /// has acknowledged them.
+    pub fn spawn_flush(self: &Arc<Self>) {
+        let solver = Arc::clone(self);
+
+        Task::new("flush")
+            .restart(Restart::Always)
+            .watchdog(FLUSH_WATCHDOG)
+            .spawn(move || loop {
+                supervisor::beat_for(FLUSH_IDLE + ACK_TIMEOUT);
+
+                match solver.flush_batch() {
+                    Ok(0) => thread::sleep(FLUSH_IDLE),
+                    Ok(count) => info!("Flushed {} queued messages", count),
+                    Err(e) => {
+                        warn!("Error flushing queue: {:?}", e);
+                        thread::sleep(FLUSH_IDLE);
                     }
                 }
 
-                client.publish(&route, message.as_str())?;
-                return Ok(());
-                // } else {
-                //     *client_opt = None;
-                // }
-            }
+                thread::sleep(FLUSH_PERIOD);
+            });
+    }
+
+    /// Samples the latest readings into the per-minute history.
+    pub fn spawn_history(self: &Arc<Self>) {
+        let solver = Arc::clone(self);
+
+        Task::new("history")
+            .restart(Restart::Always)
+            .watchdog(HISTORY_WATCHDOG)
+            .spawn(move || loop {
+                supervisor::beat_for(HISTORY_PERIOD);
+                thread::sleep(HISTORY_PERIOD);
+
+                let vitals = solver
+                    .vitals
+                    .lock()
+                    .map(|vitals| *vitals)
+                    .unwrap_or_default();
+
+                if let Ok(mut history) = solver.history.lock() {
+                    history.record(&vitals, time::uptime_ms());
+                }
+            });
+    }
+
+    fn flush_batch(&self) -> Result<usize> {
+        if !client::is_connected() || !time::is_synced() {
+            return Ok(0);
+        }
 
-            if let Ok(mut storage) = self.storage.lock() {
-                if storage.len() < LIMIT {
-                    storage.insert(0, (route, message));
-                } else {
-                    storage.remove(0);
-                    storage.insert(0, (route, message));
+        let entries = match self.storage.lock() {
+            Ok(mut storage) => storage.peek(FLUSH_BATCH)?,
+            Err(_) => return Ok(0),
+        };
+
+        if entries.is_empty() {
+            return Ok(0);
+        }
+
+        let mut ids = Vec::new();
+
+        if let Ok(client) = self.client.lock() {
+            if let Some(client) = client.as_ref() {
+                for entry in entries.iter() {
+                    let message = time::correct(&entry.message);
+                    let message = message.as_deref().unwrap_or(&entry.message);
+                    ids.push(client.publish_reliable(&entry.route, message)?);
                 }
             }
         }
 
-        Ok(())
+        if ids.is_empty() {
+            return Ok(0);
+        }
+
+        let started = Instant::now();
+        let acknowledged = loop {
+            let acknowledged = self.client.lock().map_or(0, |client| {
+                client.as_ref().map_or(0, |client| {
+                    ids.iter()
+                        .take_while(|id| client.is_acknowledged(**id))
+                        .count()
+                })
+            });
+
+            if acknowledged == ids.len() || started.elapsed() >= ACK_TIMEOUT {
+                break acknowledged;
+            }
+
+            thread::sleep(ACK_POLL);
+        };
+
+        // A full queue may have dropped or thinned entries meanwhile, so only
+        // remove the front if it is still what was published.
+        let mut storage = self
+            .storage
+            .lock()
+            .map_err(|_| anyhow!("Storage lock poisoned"))?;
+        let front = storage.peek(acknowledged)?;
+        let count = front
+            .iter()
+            .zip(entries.iter())
+            .take_while(|(a, b)| a == b)
+            .count();
+
+        storage.pop(count)?;
+        status::update(|status| status.queue = storage.len());
+        Ok(count)
+    }
+
+    /// Publishes a message to the database past the queue, which may thin
+    /// it out, retrying until the broker acknowledges it. `on_attempt` is
+    /// told the number of each try.
+    pub fn deliver(&self, message: Message, mut on_attempt: impl FnMut(u32)) -> Result<()> {
+        let route = format!("{}/{}", DATABASE, message.payload.get_topic());
+        let message = message.into_json()?;
+        let mut backoff = Backoff::new(DELIVER_RETRY_MIN, DELIVER_RETRY_MAX, unsafe {
+            esp_idf_sys::esp_random()
+        });
+        let mut attempt = 0;
+
+        loop {
+            attempt += 1;
+            on_attempt(attempt);
+            supervisor::beat_for(ACK_TIMEOUT);
+
+            let corrected = time::correct(&message);
+            let message = corrected.as_deref().unwrap_or(&message);
+
+            match self.publish_acknowledged(&route, message) {
+                Ok(true) => return Ok(()),
+                Ok(false) => info!("{} not acknowledged, retrying", route),
+                Err(e) => warn!("Failed to deliver {}, retrying: {:?}", route, e),
+            }
+
+            let delay = backoff.next_delay();
+            supervisor::beat_for(delay);
+            thread::sleep(delay);
+        }
+    }
+
+    fn publish_acknowledged(&self, route: &str, message: &str) -> Result<bool> {
+        if !client::is_connected() {
+            return Ok(false);
+        }
+
+        let id = match self.client.lock() {
+            Ok(client) => match client.as_ref() {
+                Some(client) => client.publish_reliable(route, message)?,
+                None => return Ok(false),
+            },
+            Err(_) => return Ok(false),
+        };
+
+        let started = Instant::now();
+
+        while started.elapsed() < ACK_TIMEOUT {
+            let acknowledged = self.client.lock().is_ok_and(|client| {
+                client
+                    .as_ref()
+                    .is_some_and(|client| client.is_acknowledged(id))
+            });
+
+            if acknowledged {
+                return Ok(true);
+            }
+
+            thread::sleep(ACK_POLL);
+        }
+
+        Ok(false)
     }
 
     pub fn send_to_socket(&self, message: Message) -> Result<()> {
         let route = format!("{}/{}", SOCKET, message.payload.get_topic());
-        let message = serde_json::to_string(&message)?;
+        let message = message.into_json()?;
 
         if let Ok(mut client) = self.client.lock() {
             if let Some(client) = client.as_mut() {
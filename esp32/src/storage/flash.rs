@@ -0,0 +1,67 @@
+use super::{Cursor, Entry, Slots};
+use anyhow::Result;
+use esp_idf_svc::nvs::{EspCustomNvsPartition, EspNvs, NvsCustom};
+use serde::{de::DeserializeOwned, Serialize};
+
+// The data partition from partitions.csv, kept apart from the default one
+// holding the Wi-Fi calibration and settings.
+const PARTITION: &str = "queue";
+const NAMESPACE: &str = "queue";
+const CURSOR: &str = "cursor";
+const BUFFER_SIZE: usize = 1024;
+
+/// Slots kept in NVS so queued messages survive a reboot.
+pub struct Flash {
+    nvs: EspNvs<NvsCustom>,
+}
+
+fn key(slot: usize) -> String {
+    format!("q{}", slot)
+}
+
+impl Flash {
+    pub fn new() -> Result<Self> {
+        let partition = EspCustomNvsPartition::take(PARTITION)?;
+
+        Ok(Self {
+            nvs: EspNvs::new(partition, NAMESPACE, true)?,
+        })
+    }
+
+    fn get<T: DeserializeOwned>(&self, key: &str) -> Result<Option<T>> {
+        let mut buf = [0u8; BUFFER_SIZE];
+
+        match self.nvs.get_raw(key, &mut buf)? {
+            Some(data) => Ok(Some(serde_json::from_slice(data)?)),
+            None => Ok(None),
+        }
+    }
+
+    fn set<T: Serialize>(&mut self, key: &str, value: &T) -> Result<()> {
+        self.nvs.set_raw(key, &serde_json::to_vec(value)?)?;
+        Ok(())
+    }
+}
+
+impl Slots for Flash {
+    fn load(&mut self, slot: usize) -> Result<Option<Entry>> {
+        self.get(&key(slot))
+    }
+
+    fn store(&mut self, slot: usize, entry: &Entry) -> Result<()> {
+        self.set(&key(slot), entry)
+    }
+
+    fn erase(&mut self, slot: usize) -> Result<()> {
+        self.nvs.remove(&key(slot))?;
+        Ok(())
+    }
+
+    fn load_cursor(&mut self) -> Result<Option<Cursor>> {
+        self.get(CURSOR)
+    }
+
+    fn store_cursor(&mut self, cursor: &Cursor) -> Result<()> {
+        self.set(CURSOR, cursor)
+    }
+}
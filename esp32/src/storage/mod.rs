@@ -0,0 +1,5 @@
+pub use common::storage::*;
+
+pub mod flash;
+
+pub use flash::Flash;
@@ -1,7 +1,10 @@
 use crate::{
     client::{self, Client},
     network::Network,
-    utils, PASSWORD, SSID,
+    utils::{
+        self, settings,
+        supervisor::{Restart, Task},
+    },
 };
 use anyhow::Result;
 use esp_idf_svc::{
@@ -11,18 +14,16 @@ use esp_idf_svc::{
 };
 use std::{
     sync::{Arc, Mutex},
-    thread,
+    time::Duration,
 };
 
+const WATCHDOG: Duration = Duration::from_secs(30);
+
 pub fn handle(wifi: Arc<Network>, client_opt: Arc<Mutex<Option<Client>>>) -> Result<()> {
-    wifi.connect(SSID, PASSWORD)?;
+    let settings = settings::get();
+    wifi.connect(&settings.ssid, &settings.password)?;
     utils::sntp::init()?;
-    client::create_client(client_opt.clone())?;
-
-    // let client_opt_clone = Arc::clone(&client_opt);
-    wifi.listen(move || {
-        // let _ = client::create_client(client_opt_clone.clone());
-    })
+    client::supervise(wifi, client_opt)
 }
 
 pub fn init(
@@ -35,11 +36,12 @@ pub fn init(
 
     let network_clone = Arc::clone(&network);
     let client_clone = Arc::clone(&client);
-    thread::spawn(move || {
-        if let Err(e) = handle(network_clone, client_clone) {
-            println!("Error: {:?}", e);
-        }
-    });
+    // Not restarted: the connection supervisor already retries on its own
+    // and a new run would register a second network listener.
+    Task::new("mqtt")
+        .restart(Restart::Never)
+        .watchdog(WATCHDOG)
+        .spawn(move || handle(network_clone.clone(), client_clone.clone()));
 
     Ok((network, client))
 }
@@ -0,0 +1,52 @@
+use crate::utils::{nvs, settings};
+use anyhow::Result;
+use std::sync::Mutex;
+
+pub use common::utils::config::*;
+
+static CURRENT: Mutex<Option<Config>> = Mutex::new(None);
+
+pub fn topic() -> String {
+    format!("{}/{}", CONFIG, settings::get().device)
+}
+
+/// Loads the stored configuration, falling back to the defaults.
+pub fn init() -> Result<()> {
+    let config = match nvs::get::<Config>(CONFIG) {
+        Ok(Some(config)) if config.validate().is_ok() => config,
+        Ok(_) => Config::default(),
+        Err(e) => {
+            log::info!("Failed to load config: {:?}", e);
+            Config::default()
+        }
+    };
+
+    if let Ok(mut current) = CURRENT.lock() {
+        *current = Some(config);
+    }
+
+    Ok(())
+}
+
+pub fn get() -> Config {
+    CURRENT
+        .lock()
+        .ok()
+        .and_then(|current| *current)
+        .unwrap_or_default()
+}
+
+/// Validates, stores and activates a partial update.
+pub fn apply(data: &str) -> Result<Config> {
+    let config = get().patch(data)?;
+
+    if config != get() {
+        nvs::set(CONFIG, &config)?;
+    }
+
+    if let Ok(mut current) = CURRENT.lock() {
+        *current = Some(config);
+    }
+
+    Ok(config)
+}
@@ -0,0 +1,168 @@
+use crate::{
+    client::{self, Client},
+    utils::{
+        clock::Stamp,
+        rate::Limiter,
+        settings,
+        supervisor::{self, Restart, Task},
+        time,
+    },
+};
+use anyhow::Result;
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::VecDeque,
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc, Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+pub const LOGS: &str = "logs";
+
+const BUFFER: usize = 32;
+const BURST: u32 = 10;
+const REFILL: Duration = Duration::from_secs(6);
+const FLUSH: Duration = Duration::from_secs(1);
+const WATCHDOG: Duration = Duration::from_secs(30);
+
+static LOGGER: Logger = Logger;
+static PENDING: Mutex<VecDeque<Entry>> = Mutex::new(VecDeque::new());
+static DROPPED: AtomicU32 = AtomicU32::new(0);
+
+#[derive(Serialize, Deserialize)]
+pub struct Entry {
+    pub headers: Stamp,
+    pub level: String,
+    pub target: String,
+    pub message: String,
+    /// Records lost to a full buffer since the previous entry was sent.
+    pub dropped: u32,
+}
+
+/// Writes every record to the serial port and keeps warnings and errors for
+/// the server.
+struct Logger;
+
+impl Log for Logger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= Level::Info
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        let letter = match record.level() {
+            Level::Error => 'E',
+            Level::Warn => 'W',
+            Level::Info => 'I',
+            Level::Debug => 'D',
+            Level::Trace => 'V',
+        };
+
+        println!(
+            "{} ({}) {}: {}",
+            letter,
+            time::uptime_ms(),
+            record.target(),
+            record.args()
+        );
+
+        if record.level() > Level::Warn {
+            return;
+        }
+
+        let entry = Entry {
+            headers: time::stamp(),
+            level: record.level().as_str().to_lowercase(),
+            target: record.target().to_string(),
+            message: record.args().to_string(),
+            dropped: 0,
+        };
+
+        // Never wait here, the forwarder itself may be logging.
+        match PENDING.try_lock() {
+            Ok(mut pending) => {
+                if pending.len() == BUFFER {
+                    pending.pop_front();
+                    DROPPED.fetch_add(1, Ordering::Relaxed);
+                }
+
+                pending.push_back(entry);
+            }
+            Err(_) => {
+                DROPPED.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+pub fn init() -> Result<()> {
+    log::set_logger(&LOGGER).map_err(|e| anyhow::anyhow!("{:?}", e))?;
+    log::set_max_level(LevelFilter::Info);
+    Ok(())
+}
+
+fn topic() -> String {
+    format!("{}/{}", LOGS, settings::get().device)
+}
+
+fn publish(client: &Mutex<Option<Client>>, entry: &Entry) -> Result<bool> {
+    let Ok(client) = client.lock() else {
+        return Ok(false);
+    };
+
+    let Some(client) = client.as_ref() else {
+        return Ok(false);
+    };
+
+    client.publish_reliable(&topic(), &serde_json::to_string(entry)?)?;
+    Ok(true)
+}
+
+/// Sends the buffered records to `logs/<device>`, at most `BURST` at once
+/// and one every `REFILL` after that.
+pub fn forward(client: Arc<Mutex<Option<Client>>>) {
+    let start = Instant::now();
+    let mut limiter = Limiter::new(BURST, REFILL);
+
+    Task::new("logs")
+        .restart(Restart::Always)
+        .watchdog(WATCHDOG)
+        .spawn(move || loop {
+            supervisor::beat_for(FLUSH);
+            thread::sleep(FLUSH);
+
+            while client::is_connected() {
+                let Some(mut entry) = PENDING.lock().ok().and_then(|mut p| p.pop_front()) else {
+                    break;
+                };
+
+                if !limiter.allow(start.elapsed()) {
+                    if let Ok(mut pending) = PENDING.lock() {
+                        pending.push_front(entry);
+                    }
+
+                    break;
+                }
+
+                entry.headers = time::resolve(entry.headers);
+                entry.dropped += DROPPED.swap(0, Ordering::Relaxed);
+
+                if !matches!(publish(&client, &entry), Ok(true)) {
+                    if let Ok(mut pending) = PENDING.lock() {
+                        pending.push_front(entry);
+                    }
+
+                    break;
+                }
+            }
+        });
+}
@@ -1,3 +1,14 @@
 pub mod driver;
 pub mod sntp;
-pub mod check;
\ No newline at end of file
+pub mod nvs;
+pub mod scheduler;
+pub mod settings;
+pub mod config;
+pub mod supervisor;
+pub mod time;
+pub mod logger;
+
+pub use common::utils::{
+    battery, calibration, check, clock, connection, countdown, gesture, haptics, history, rate,
+    status, vitals,
+};
@@ -0,0 +1,55 @@
+use anyhow::{anyhow, Result};
+use esp_idf_svc::nvs::{EspDefaultNvsPartition, EspNvs, NvsDefault};
+use serde::{de::DeserializeOwned, Serialize};
+use std::sync::{Mutex, OnceLock};
+
+const NAMESPACE: &str = "microtime";
+const BUFFER_SIZE: usize = 1024;
+
+static NVS: OnceLock<Mutex<EspNvs<NvsDefault>>> = OnceLock::new();
+
+pub fn init(partition: EspDefaultNvsPartition) -> Result<()> {
+    if NVS.get().is_none() {
+        let nvs = EspNvs::new(partition, NAMESPACE, true)?;
+        let _ = NVS.set(Mutex::new(nvs));
+    }
+
+    Ok(())
+}
+
+fn with<R>(f: impl FnOnce(&mut EspNvs<NvsDefault>) -> Result<R>) -> Result<R> {
+    let nvs = NVS.get().ok_or_else(|| anyhow!("NVS not initialized"))?;
+    let mut nvs = nvs.lock().map_err(|_| anyhow!("NVS lock poisoned"))?;
+    f(&mut nvs)
+}
+
+pub fn get_raw(key: &str, buf: &mut [u8]) -> Result<Option<usize>> {
+    with(|nvs| Ok(nvs.get_raw(key, buf)?.map(|data| data.len())))
+}
+
+pub fn set_raw(key: &str, data: &[u8]) -> Result<()> {
+    with(|nvs| {
+        nvs.set_raw(key, data)?;
+        Ok(())
+    })
+}
+
+pub fn remove(key: &str) -> Result<()> {
+    with(|nvs| {
+        nvs.remove(key)?;
+        Ok(())
+    })
+}
+
+pub fn get<T: DeserializeOwned>(key: &str) -> Result<Option<T>> {
+    let mut buf = [0u8; BUFFER_SIZE];
+
+    match get_raw(key, &mut buf)? {
+        Some(len) => Ok(Some(serde_json::from_slice(&buf[..len])?)),
+        None => Ok(None),
+    }
+}
+
+pub fn set<T: Serialize>(key: &str, value: &T) -> Result<()> {
+    set_raw(key, &serde_json::to_vec(value)?)
+}
@@ -0,0 +1,204 @@
+use crate::{
+    solver::{Message, Payload, Solver},
+    utils::{
+        check::Check,
+        config::{self, Config},
+        supervisor::{self, Restart, Task},
+        vitals::Vitals,
+    },
+};
+use anyhow::Result;
+use log::warn;
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Mutex},
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+
+const MAX_ERRORS_PER_MINUTE: u32 = 20;
+const REINIT_DELAY: Duration = Duration::from_secs(5);
+const WATCHDOG: Duration = Duration::from_secs(30);
+
+static ERRORS: Mutex<BTreeMap<&'static str, u32>> = Mutex::new(BTreeMap::new());
+
+/// A device that produces one sample per scheduler tick.
+pub trait Sensor: Send + 'static {
+    type Sample;
+
+    fn name(&self) -> &'static str;
+
+    /// Brings the device back after repeated sampling errors.
+    fn init(&mut self) -> Result<()>;
+
+    fn sample(&mut self) -> Result<Self::Sample>;
+}
+
+type Consumer<T> = Box<dyn FnMut(&T, &Solver) + Send>;
+
+/// Reads a period or a decimation from the active configuration, so changes
+/// apply on the next tick.
+pub type Setting<T> = fn(&Config) -> T;
+
+struct Sink<T> {
+    every: Setting<u32>,
+    consumer: Consumer<T>,
+}
+
+/// Samples a sensor once per tick and hands every sample to its sinks, each
+/// one running only on every `every`-th successful sample.
+pub struct Scheduler<S: Sensor> {
+    sensor: S,
+    tick: Setting<Duration>,
+    max_errors: u32,
+    sinks: Vec<Sink<S::Sample>>,
+    failing: Vec<Consumer<u32>>,
+    // Samples failed in a row, kept across restarts of the task.
+    failures: u32,
+}
+
+impl<S: Sensor> Scheduler<S> {
+    pub fn new(sensor: S, tick: Setting<Duration>) -> Self {
+        Self {
+            sensor,
+            tick,
+            max_errors: MAX_ERRORS_PER_MINUTE,
+            sinks: Vec::new(),
+            failing: Vec::new(),
+            failures: 0,
+        }
+    }
+
+    pub fn max_errors(mut self, max_errors_per_minute: u32) -> Self {
+        self.max_errors = max_errors_per_minute;
+        self
+    }
+
+    pub fn sink<F>(mut self, every: Setting<u32>, consumer: F) -> Self
+    where
+        F: FnMut(&S::Sample, &Solver) + Send + 'static,
+    {
+        self.sinks.push(Sink {
+            every,
+            consumer: Box::new(consumer),
+        });
+        self
+    }
+
+    /// Calls `consumer` after every failed sample with how many failed in a
+    /// row.
+    pub fn failing<F>(mut self, consumer: F) -> Self
+    where
+        F: FnMut(&u32, &Solver) + Send + 'static,
+    {
+        self.failing.push(Box::new(consumer));
+        self
+    }
+
+    /// Publishes the payloads built from a sample to the socket.
+    pub fn live<P, F>(self, every: Setting<u32>, payloads: F) -> Self
+    where
+        P: Into<Payload>,
+        F: Fn(&S::Sample) -> Vec<P> + Send + 'static,
+    {
+        self.sink(every, move |sample, solver| {
+            for payload in payloads(sample) {
+                let _ = solver.send_to_socket(Message::new(payload));
+            }
+        })
+    }
+
+    /// Stores the payloads built from a sample in the database.
+    pub fn persisted<P, F>(self, every: Setting<u32>, payloads: F) -> Self
+    where
+        P: Into<Payload>,
+        F: Fn(&S::Sample) -> Vec<P> + Send + 'static,
+    {
+        self.sink(every, move |sample, solver| {
+            for payload in payloads(sample) {
+                let _ = solver.send_to_database(Message::new(payload));
+            }
+        })
+    }
+
+    /// Updates the latest values shown on the device.
+    pub fn display<F>(self, every: Setting<u32>, mut update: F) -> Self
+    where
+        F: FnMut(&S::Sample, &mut Vitals) + Send + 'static,
+    {
+        self.sink(every, move |sample, solver| {
+            if let Ok(mut vitals) = solver.vitals.lock() {
+                update(sample, &mut vitals);
+            }
+        })
+    }
+
+    /// Runs the scheduler as a supervised task, restarted whenever the
+    /// sensor cannot be brought back.
+    pub fn spawn(mut self, solver: Arc<Solver>) -> JoinHandle<()> {
+        Task::new(self.sensor.name())
+            .restart(Restart::Always)
+            .watchdog(WATCHDOG)
+            .spawn(move || self.run(&solver))
+    }
+
+    fn run(&mut self, solver: &Solver) -> Result<()> {
+        let mut check = Check::new(self.max_errors);
+        let mut count: u64 = 0;
+
+        loop {
+            let started = Instant::now();
+            let config = config::get();
+
+            match self.sensor.sample() {
+                Ok(sample) => {
+                    self.failures = 0;
+
+                    for sink in self.sinks.iter_mut() {
+                        if count % (sink.every)(&config).max(1) as u64 == 0 {
+                            (sink.consumer)(&sample, solver);
+                        }
+                    }
+
+                    count += 1;
+                }
+                Err(e) => {
+                    warn!("Error reading {}: {:?}", self.sensor.name(), e);
+                    check.error();
+
+                    self.failures += 1;
+
+                    for consumer in self.failing.iter_mut() {
+                        consumer(&self.failures, solver);
+                    }
+
+                    if let Ok(mut errors) = ERRORS.lock() {
+                        *errors.entry(self.sensor.name()).or_insert(0) += 1;
+                    }
+
+                    if check.is_limit() {
+                        supervisor::beat_for(REINIT_DELAY);
+                        thread::sleep(REINIT_DELAY);
+
+                        self.sensor.init()?;
+                        check.reset();
+                    }
+                }
+            }
+
+            let delay = (self.tick)(&config).saturating_sub(started.elapsed());
+            supervisor::beat_for(delay);
+            thread::sleep(delay);
+        }
+    }
+}
+
+/// Sampling errors of every sensor since boot.
+pub fn errors() -> BTreeMap<String, u32> {
+    ERRORS.lock().map_or(BTreeMap::new(), |errors| {
+        errors
+            .iter()
+            .map(|(name, count)| (name.to_string(), *count))
+            .collect()
+    })
+}
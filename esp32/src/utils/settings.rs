@@ -0,0 +1,30 @@
+use crate::utils::nvs;
+use anyhow::Result;
+use std::sync::OnceLock;
+
+pub use common::utils::settings::*;
+
+const SETTINGS: &str = "settings";
+
+static CURRENT: OnceLock<Settings> = OnceLock::new();
+
+pub fn load() -> Result<Option<Settings>> {
+    Ok(nvs::get::<Settings>(SETTINGS)?.filter(|settings| settings.validate().is_ok()))
+}
+
+pub fn save(settings: &Settings) -> Result<()> {
+    settings.validate()?;
+    nvs::set(SETTINGS, settings)
+}
+
+/// Loads the stored settings once, falling back to the compiled defaults.
+pub fn init() -> Result<bool> {
+    let stored = load()?;
+    let provisioned = stored.is_some();
+    let _ = CURRENT.set(stored.unwrap_or_default());
+    Ok(provisioned)
+}
+
+pub fn get() -> &'static Settings {
+    CURRENT.get_or_init(Settings::default)
+}
@@ -1,16 +1,28 @@
+use crate::utils::{
+    supervisor::{self, Task},
+    time,
+};
 use anyhow::Result;
 use esp_idf_svc::sntp::{EspSntp, SyncStatus};
 use std::{thread, time::Duration};
 
+const POLL: Duration = Duration::from_secs(1);
+const WATCHDOG: Duration = Duration::from_secs(30);
+
+/// Starts SNTP in the background, so a network without NTP does not hold
+/// the device back. Every completed sync is passed to the time service.
 pub fn init() -> Result<()> {
     let sntp = EspSntp::new_default()?;
-    log::info!("SNTP initialized, waiting for status!");
+    log::info!("SNTP initialized");
 
-    while sntp.get_sync_status() != SyncStatus::Completed {
-        thread::sleep(Duration::from_millis(200));
-    }
+    Task::new("sntp").watchdog(WATCHDOG).spawn(move || loop {
+        if sntp.get_sync_status() == SyncStatus::Completed {
+            time::sntp_synced();
+        }
 
-    log::info!("SNTP status received!");
+        supervisor::beat_for(POLL);
+        thread::sleep(POLL);
+    });
 
     Ok(())
-}
\ No newline at end of file
+}
@@ -0,0 +1,294 @@
+use crate::{
+    solver::{Message, Solver},
+    utils::connection::Backoff,
+};
+use anyhow::Result;
+use esp_idf_sys::{esp, esp_random, esp_task_wdt_add, esp_task_wdt_reset};
+use log::{error, info, warn};
+use serde::{Deserialize, Serialize};
+use std::{
+    cell::RefCell,
+    ptr,
+    sync::{Arc, Mutex},
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+
+const RESTART_MIN: Duration = Duration::from_secs(1);
+const RESTART_MAX: Duration = Duration::from_secs(60);
+const HEALTHY_RUN: Duration = Duration::from_secs(60);
+const STUCK_GRACE: Duration = Duration::from_secs(30);
+const MONITOR_PERIOD: Duration = Duration::from_secs(1);
+const HEALTH_PERIOD: Duration = Duration::from_secs(60);
+const HEALTH_WATCHDOG: Duration = Duration::from_secs(30);
+
+static TASKS: Mutex<Vec<Arc<Slot>>> = Mutex::new(Vec::new());
+
+thread_local! {
+    static CURRENT: RefCell<Option<Arc<Slot>>> = const { RefCell::new(None) };
+}
+
+/// When a task is started again after it returns.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Restart {
+    Always,
+    OnError,
+    Never,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum State {
+    Running,
+    Waiting,
+    Failed,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Verdict {
+    Healthy,
+    Stalled,
+    Stuck,
+}
+
+struct Status {
+    state: State,
+    deadline: Option<Instant>,
+    stalled: bool,
+    restarts: u32,
+    errors: u32,
+    stalls: u32,
+}
+
+struct Slot {
+    name: &'static str,
+    watchdog: Option<Duration>,
+    status: Mutex<Status>,
+}
+
+impl Slot {
+    fn update(&self, f: impl FnOnce(&mut Status)) {
+        if let Ok(mut status) = self.status.lock() {
+            f(&mut status);
+        }
+    }
+
+    // The deadline is only armed by the first heartbeat, so a task may take
+    // as long as it needs to start.
+    fn check(&self, now: Instant) -> Verdict {
+        let Ok(mut status) = self.status.lock() else {
+            return Verdict::Healthy;
+        };
+
+        let verdict = match (status.state, status.deadline) {
+            (State::Running, Some(deadline)) if now > deadline + STUCK_GRACE => Verdict::Stuck,
+            (State::Running, Some(deadline)) if now > deadline => Verdict::Stalled,
+            _ => Verdict::Healthy,
+        };
+
+        if verdict != Verdict::Healthy && !status.stalled {
+            status.stalled = true;
+            status.stalls += 1;
+            warn!("Task {} missed its heartbeat", self.name);
+        }
+
+        verdict
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct TaskHealth {
+    pub name: String,
+    pub state: State,
+    pub stalled: bool,
+    pub restarts: u32,
+    pub errors: u32,
+    pub stalls: u32,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Health {
+    pub tasks: Vec<TaskHealth>,
+}
+
+/// A long-running task registered with the supervisor.
+pub struct Task {
+    name: &'static str,
+    restart: Restart,
+    watchdog: Option<Duration>,
+}
+
+impl Task {
+    pub fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            restart: Restart::OnError,
+            watchdog: None,
+        }
+    }
+
+    pub fn restart(mut self, restart: Restart) -> Self {
+        self.restart = restart;
+        self
+    }
+
+    /// Considers the task stuck when it goes this long without a heartbeat.
+    pub fn watchdog(mut self, timeout: Duration) -> Self {
+        self.watchdog = Some(timeout);
+        self
+    }
+
+    pub fn spawn<F>(self, mut task: F) -> JoinHandle<()>
+    where
+        F: FnMut() -> Result<()> + Send + 'static,
+    {
+        let slot = Arc::new(Slot {
+            name: self.name,
+            watchdog: self.watchdog,
+            status: Mutex::new(Status {
+                state: State::Running,
+                deadline: None,
+                stalled: false,
+                restarts: 0,
+                errors: 0,
+                stalls: 0,
+            }),
+        });
+
+        if let Ok(mut tasks) = TASKS.lock() {
+            tasks.push(slot.clone());
+        }
+
+        thread::spawn(move || {
+            CURRENT.with(|current| *current.borrow_mut() = Some(slot.clone()));
+            let mut backoff = Backoff::new(RESTART_MIN, RESTART_MAX, unsafe { esp_random() });
+
+            loop {
+                let started = Instant::now();
+                let result = task();
+
+                if let Err(e) = &result {
+                    warn!("Task {} failed: {:?}", slot.name, e);
+                    slot.update(|status| status.errors += 1);
+                }
+
+                let again = match self.restart {
+                    Restart::Always => true,
+                    Restart::OnError => result.is_err(),
+                    Restart::Never => false,
+                };
+
+                if !again {
+                    if result.is_ok() {
+                        unregister(&slot);
+                    } else {
+                        slot.update(|status| status.state = State::Failed);
+                    }
+
+                    return;
+                }
+
+                if started.elapsed() >= HEALTHY_RUN {
+                    backoff.reset();
+                }
+
+                let delay = backoff.next_delay();
+                info!("Restarting task {} in {:?}", slot.name, delay);
+
+                slot.update(|status| {
+                    status.state = State::Waiting;
+                    status.deadline = None;
+                    status.stalled = false;
+                    status.restarts += 1;
+                });
+                thread::sleep(delay);
+                slot.update(|status| status.state = State::Running);
+            }
+        })
+    }
+}
+
+fn unregister(slot: &Arc<Slot>) {
+    if let Ok(mut tasks) = TASKS.lock() {
+        tasks.retain(|current| !Arc::ptr_eq(current, slot));
+    }
+}
+
+fn tasks() -> Vec<Arc<Slot>> {
+    TASKS.lock().map_or(Vec::new(), |tasks| tasks.clone())
+}
+
+/// Tells the supervisor the calling task is still making progress.
+pub fn beat() {
+    beat_for(Duration::ZERO);
+}
+
+/// Like `beat`, for a task about to wait `expected` before its next beat.
+pub fn beat_for(expected: Duration) {
+    CURRENT.with(|current| {
+        if let Some(slot) = current.borrow().as_ref() {
+            if let Some(watchdog) = slot.watchdog {
+                slot.update(|status| {
+                    status.deadline = Some(Instant::now() + expected + watchdog);
+                    status.stalled = false;
+                });
+            }
+        }
+    });
+}
+
+pub fn health() -> Health {
+    let tasks = tasks()
+        .iter()
+        .filter_map(|slot| {
+            let status = slot.status.lock().ok()?;
+
+            Some(TaskHealth {
+                name: slot.name.to_string(),
+                state: status.state,
+                stalled: status.stalled,
+                restarts: status.restarts,
+                errors: status.errors,
+                stalls: status.stalls,
+            })
+        })
+        .collect();
+
+    Health { tasks }
+}
+
+/// Publishes the health of every task periodically.
+pub fn report(solver: Arc<Solver>) {
+    Task::new("health")
+        .restart(Restart::Always)
+        .watchdog(HEALTH_WATCHDOG)
+        .spawn(move || loop {
+            beat_for(HEALTH_PERIOD);
+            thread::sleep(HEALTH_PERIOD);
+            solver.send_to_socket(Message::new(health()))?;
+        });
+}
+
+/// Watches every heartbeat from the calling thread, which stops feeding the
+/// task watchdog once a task has been stuck past its grace period so the
+/// device reboots. A task that is only late is reported, not rebooted for.
+pub fn monitor() -> Result<()> {
+    esp!(unsafe { esp_task_wdt_add(ptr::null_mut()) })?;
+
+    loop {
+        let now = Instant::now();
+        let stuck = tasks()
+            .iter()
+            .filter(|slot| slot.check(now) == Verdict::Stuck)
+            .map(|slot| slot.name)
+            .collect::<Vec<_>>();
+
+        if stuck.is_empty() {
+            unsafe { esp_task_wdt_reset() };
+        } else {
+            error!("Tasks stuck: {:?}, waiting for the watchdog", stuck);
+        }
+
+        thread::sleep(MONITOR_PERIOD);
+    }
+}
@@ -0,0 +1,83 @@
+use crate::utils::{
+    clock::{Clock, Offer, Source, Stamp},
+    status,
+};
+use anyhow::{bail, Result};
+use esp_idf_sys::{esp_random, esp_timer_get_time, settimeofday, timeval};
+use log::info;
+use std::{
+    ptr,
+    sync::Mutex,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+pub const TIME: &str = "time";
+
+static CLOCK: Mutex<Option<Clock>> = Mutex::new(None);
+
+fn with<T>(f: impl FnOnce(&mut Clock) -> T) -> Option<T> {
+    let mut clock = CLOCK.lock().ok()?;
+    Some(f(
+        clock.get_or_insert_with(|| Clock::new(unsafe { esp_random() }))
+    ))
+}
+
+pub fn uptime_ms() -> u64 {
+    (unsafe { esp_timer_get_time() } / 1000) as u64
+}
+
+pub fn source() -> Source {
+    with(|clock| clock.source()).unwrap_or(Source::None)
+}
+
+pub fn is_synced() -> bool {
+    source() != Source::None
+}
+
+pub fn stamp() -> Stamp {
+    let uptime = uptime_ms();
+    with(|clock| clock.stamp(uptime)).unwrap_or_else(|| Clock::new(0).stamp(uptime))
+}
+
+pub fn resolve(stamp: Stamp) -> Stamp {
+    with(|clock| clock.resolve(stamp)).unwrap_or(stamp)
+}
+
+pub fn correct(message: &str) -> Option<String> {
+    with(|clock| clock.correct(message)).flatten()
+}
+
+/// Records a completed SNTP sync, which already set the system time.
+pub fn sntp_synced() {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |now| now.as_millis() as i64);
+
+    if with(|clock| clock.sync(Source::Sntp, now, uptime_ms())) == Some(true) {
+        status::update(|status| status.time = Source::Sntp);
+        info!("Time synced from SNTP");
+    }
+}
+
+/// Applies a time offer from the server, setting the system time when no
+/// recent SNTP sync outranks it.
+pub fn offer(data: &str) -> Result<()> {
+    let offer = serde_json::from_str::<Offer>(data)?;
+
+    if with(|clock| clock.sync(Source::Server, offer.unix_ms, uptime_ms())) != Some(true) {
+        return Ok(());
+    }
+
+    let time = timeval {
+        tv_sec: (offer.unix_ms / 1000) as _,
+        tv_usec: (offer.unix_ms % 1000 * 1000) as _,
+    };
+
+    if unsafe { settimeofday(&time, ptr::null()) } != 0 {
+        bail!("Failed to set the system time");
+    }
+
+    status::update(|status| status.time = Source::Server);
+    info!("Time synced from server");
+    Ok(())
+}
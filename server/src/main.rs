@@ -1,5 +1,5 @@
 #![allow(unused_variables, unused_imports, dead_code)]
-use crate::mqtt::{DS18B20, MAX3010X, MPU6050, REPORT};
+use crate::mqtt::{ACTIVITY, DS18B20, HRV, MAX3010X, MPU6050, REPORT, TELEMETRY};
 use actix::Actor;
 use actix_cors::Cors;
 use actix_web::{web, App, HttpServer};
@@ -12,8 +12,11 @@ pub const DATABASE: &str = "drivers";
 mod database;
 mod messages;
 mod mqtt;
+mod rules;
 mod services;
+mod sleep;
 mod socket;
+mod time;
 mod utils;
 
 #[actix::main]
@@ -27,14 +30,27 @@ async fn main() -> Result<()> {
     let max3010x = db.collection(MAX3010X);
     let mpu6050 = db.collection(MPU6050);
     let report = db.collection(REPORT);
+    let activity = db.collection(ACTIVITY);
+    let hrv = db.collection(HRV);
+    let telemetry = db.collection(TELEMETRY);
+    let sleep = db.collection(sleep::SLEEP);
+    let config = db.collection(mqtt::CONFIG);
+    let logs = db.collection(mqtt::LOGS);
 
-    let txs = mqtt::init(
-        ds18b20.clone(),
-        max3010x.clone(),
-        mpu6050.clone(),
-        report.clone(),
-    )
+    let (txs, publisher) = mqtt::init(mqtt::Collections {
+        ds18b20: ds18b20.clone(),
+        max3010x: max3010x.clone(),
+        mpu6050: mpu6050.clone(),
+        report: report.clone(),
+        activity: activity.clone(),
+        hrv: hrv.clone(),
+        config: config.clone(),
+        logs: logs.clone(),
+        telemetry: telemetry.clone(),
+    })
     .await?;
+    sleep::init(activity.clone(), max3010x.clone(), sleep.clone());
+    time::init(publisher.clone());
     let socket = socket::Server::new().start();
 
     HttpServer::new(move || {
@@ -43,15 +59,33 @@ async fn main() -> Result<()> {
         App::new()
             .wrap(cors)
             .app_data(web::Data::new(txs.clone()))
+            .app_data(web::Data::new(publisher.clone()))
             .app_data(web::Data::new(socket.clone()))
             .app_data(web::Data::new(max3010x.clone()))
             .app_data(web::Data::new(mpu6050.clone()))
             .app_data(web::Data::new(ds18b20.clone()))
             .app_data(web::Data::new(report.clone()))
+            .app_data(web::Data::new(activity.clone()))
+            .app_data(web::Data::new(sleep.clone()))
+            .app_data(web::Data::new(hrv.clone()))
+            .app_data(web::Data::new(config.clone()))
+            .app_data(web::Data::new(logs.clone()))
+            .app_data(web::Data::new(telemetry.clone()))
             .service(services::temperature::get_values)
+            .service(services::temperature::get_probes)
             .service(services::report::get_values)
             .service(services::steps::get_values)
             .service(services::heart_rate::get_values)
+            .service(services::activity::get_values)
+            .service(services::sleep::get_values)
+            .service(services::hrv::get_values)
+            .service(services::calibration::set_values)
+            .service(services::alarm::set_values)
+            .service(services::haptics::play)
+            .service(services::config::set_values)
+            .service(services::config::get_values)
+            .service(services::logs::get_values)
+            .service(services::telemetry::get_values)
             .route("/ws/", web::get().to(socket::route))
     })
     .bind((HOST, PORT.parse::<u16>()?))?
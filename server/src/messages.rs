@@ -1,12 +1,28 @@
 use mongodb::bson::oid::ObjectId;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Headers {
     pub timestamp: i64,
+    // Devices stamp readings with their uptime until they know the time.
+    #[serde(default = "synced")]
+    pub synced: bool,
+}
+
+fn synced() -> bool {
+    true
 }
 
 impl Headers {
+    /// Replaces an uptime stamp with the time the server `received` it. The
+    /// headers stay unsynced, so these readings can be told apart.
+    pub fn settle(&mut self, received: i64) {
+        if !self.synced {
+            self.timestamp = received;
+        }
+    }
+
     pub fn get_timestamp(&self) -> i64 {
         self.timestamp + chrono::Local::now().offset().local_minus_utc() as i64
     }
@@ -20,22 +36,57 @@ pub struct Message<P> {
     pub payload: P,
 }
 
+impl<P> Message<P> {
+    pub fn new(headers: Headers, payload: P) -> Self {
+        Self {
+            id: None,
+            headers,
+            payload,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Priority {
+    #[default]
+    Normal,
+    High,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Vitals {
+    pub heart_rate: Option<u32>,
+    pub temperature: Option<f32>,
+    #[serde(default)]
+    pub steps: u32,
+    pub activity: Option<String>,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Report {
     pub status: String,
     pub description: String,
+    #[serde(default)]
+    pub priority: Priority,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vitals: Option<Vitals>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Ds18b20 {
+    #[serde(default)]
+    pub rom: String,
     pub temperature: f32,
+    #[serde(default)]
+    pub raw: f32,
 }
 
 #[derive(Serialize, Deserialize)]
 pub struct Max3010x {
     // pub red: u32,
     // pub ir: u32,
-    pub heart_rate: u32
+    pub heart_rate: u32,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -54,5 +105,101 @@ pub struct Rotation {
 
 #[derive(Serialize, Deserialize)]
 pub struct Mpu6050 {
-    pub steps: u32
-}
\ No newline at end of file
+    pub steps: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Activity {
+    pub activity: String,
+    pub intensity: f32,
+    pub calories: f32,
+    pub duration: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Hrv {
+    pub rmssd: f32,
+    pub sdnn: f32,
+    pub pnn50: f32,
+    pub intervals: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct Reference {
+    pub raw: f32,
+    pub actual: f32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct Calibration {
+    pub offset: f32,
+    pub gain: f32,
+    pub reference: Option<[Reference; 2]>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DeviceConfig {
+    pub device: String,
+    pub config: serde_json::Value,
+    pub timestamp: i64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct Offer {
+    pub unix_ms: i64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Log {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    id: Option<ObjectId>,
+    #[serde(default)]
+    pub device: String,
+    pub headers: Headers,
+    pub level: String,
+    pub target: String,
+    pub message: String,
+    #[serde(default)]
+    pub dropped: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Telemetry {
+    #[serde(default)]
+    pub device: String,
+    pub voltage: Option<f32>,
+    pub battery: Option<u8>,
+    pub rssi: Option<i8>,
+    pub uptime: u64,
+    pub heap: u32,
+    pub min_heap: u32,
+    pub queue: usize,
+    #[serde(default)]
+    pub errors: HashMap<String, u32>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn treats_headers_without_a_flag_as_synced() {
+        let mut headers = serde_json::from_str::<Headers>(r#"{"timestamp":1700000000}"#).unwrap();
+        headers.settle(1800000000);
+
+        assert!(headers.synced);
+        assert_eq!(headers.timestamp, 1700000000);
+    }
+
+    #[test]
+    fn stamps_unsynced_headers_on_arrival() {
+        let mut headers = serde_json::from_str::<Headers>(
+            r#"{"timestamp":42,"uptime":42000,"boot":7,"synced":false}"#,
+        )
+        .unwrap();
+        headers.settle(1800000000);
+
+        assert!(!headers.synced);
+        assert_eq!(headers.timestamp, 1800000000);
+    }
+}
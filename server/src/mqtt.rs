@@ -1,10 +1,21 @@
-use crate::messages::{Ds18b20, Max3010x, Message, Mpu6050, Report};
+use crate::{
+    messages::{
+        Activity, DeviceConfig, Ds18b20, Hrv, Log, Max3010x, Message, Mpu6050, Priority, Report,
+        Telemetry,
+    },
+    rules,
+};
 use anyhow::Result;
-use mongodb::Collection;
+use mongodb::{
+    bson::doc,
+    options::{FindOneOptions, ReplaceOptions},
+    Collection,
+};
 use rumqttc::v5::{
     mqttbytes::{v5::Publish, QoS},
     AsyncClient, Event, Incoming, MqttOptions,
 };
+use serde::{de::DeserializeOwned, Serialize};
 use std::{collections::HashMap, sync::Arc, time::Duration};
 use tokio::{
     sync::broadcast::{self, Sender},
@@ -17,27 +28,111 @@ pub const PORT: &str = "1884";
 
 pub const SOCKET: &str = "socket";
 pub const DATABASE: &str = "database";
+pub const COMMAND: &str = "command";
+pub const CONFIG: &str = "config";
+pub const LOGS: &str = "logs";
 
 pub const DS18B20: &str = "ds18b20";
 pub const MAX3010X: &str = "max3010x";
 pub const MPU6050: &str = "mpu6050";
 pub const REPORT: &str = "report";
+pub const ACTIVITY: &str = "activity";
+pub const HRV: &str = "hrv";
+pub const HEALTH: &str = "health";
+pub const TELEMETRY: &str = "telemetry";
 
 pub const RED_UPDATES: [&str; 2] = [SOCKET, DATABASE];
-pub const PAYLOADS: [&str; 4] = [DS18B20, MAX3010X, MPU6050, REPORT];
+pub const PAYLOADS: [&str; 8] = [
+    DS18B20, MAX3010X, MPU6050, REPORT, ACTIVITY, HRV, HEALTH, TELEMETRY,
+];
+
+#[derive(Clone)]
+pub struct Collections {
+    pub ds18b20: Collection<Message<Ds18b20>>,
+    pub max3010x: Collection<Message<Max3010x>>,
+    pub mpu6050: Collection<Message<Mpu6050>>,
+    pub report: Collection<Message<Report>>,
+    pub activity: Collection<Message<Activity>>,
+    pub hrv: Collection<Message<Hrv>>,
+    pub config: Collection<DeviceConfig>,
+    pub logs: Collection<Log>,
+    pub telemetry: Collection<Message<Telemetry>>,
+}
+
+async fn handle_telemetry(
+    message: Message<Telemetry>,
+    txs: &HashMap<String, Sender<String>>,
+    collections: &Collections,
+) -> Result<()> {
+    let latest = FindOneOptions::builder()
+        .sort(doc! { "headers.timestamp": -1 })
+        .build();
+    let previous = collections
+        .telemetry
+        .find_one(doc! { "payload.device": &message.payload.device }, latest)
+        .await?;
+    collections.telemetry.insert_one(&message, None).await?;
+
+    let previous = previous.as_ref().map(|previous| &previous.payload);
+
+    if let Some(report) = rules::low_battery(previous, &message.payload) {
+        let report = Message::new(message.headers.clone(), report);
+        collections.report.insert_one(&report, None).await?;
+
+        if let Some(tx) = txs.get(REPORT) {
+            let _ = tx.send(serde_json::to_string(&report)?);
+        }
+    }
+
+    Ok(())
+}
+
+/// Parses a device message, giving readings taken before the device knew the
+/// time the time they arrived.
+fn parse<P: DeserializeOwned>(payload: &str) -> Result<Message<P>> {
+    let mut message = serde_json::from_str::<Message<P>>(payload)?;
+    message.headers.settle(chrono::Utc::now().timestamp());
+    Ok(message)
+}
 
 pub async fn handle(
     publish: &Publish,
     txs: Arc<HashMap<String, Sender<String>>>,
-    ds18b20: &Collection<Message<Ds18b20>>,
-    max3010x: &Collection<Message<Max3010x>>,
-    mpu6050: &Collection<Message<Mpu6050>>,
-    report: &Collection<Message<Report>>,
+    collections: &Collections,
 ) -> Result<()> {
     let topic = std::str::from_utf8(&publish.topic)?;
     let payload = std::str::from_utf8(&publish.payload)?.to_string();
     let routes = topic.split('/').collect::<Vec<&str>>();
 
+    if let [CONFIG, device] = routes[..] {
+        let config = DeviceConfig {
+            device: device.to_string(),
+            config: serde_json::from_str(&payload)?,
+            timestamp: chrono::Utc::now().timestamp(),
+        };
+
+        collections
+            .config
+            .replace_one(
+                doc! { "device": device },
+                config,
+                ReplaceOptions::builder().upsert(true).build(),
+            )
+            .await?;
+
+        return Ok(());
+    }
+
+    if let [LOGS, device] = routes[..] {
+        let mut log = serde_json::from_str::<Log>(&payload)?;
+        log.device = device.to_string();
+        log.headers.settle(chrono::Utc::now().timestamp());
+        println!("LOG {} => {} {}", device, log.level, log.message);
+        collections.logs.insert_one(log, None).await?;
+
+        return Ok(());
+    }
+
     if routes.len() == 2 {
         let update = routes[0];
         let driver = routes[1];
@@ -48,20 +143,40 @@ pub async fn handle(
                     println!("DATABASE => {}", payload);
                     match driver {
                         DS18B20 => {
-                            let message = serde_json::from_str::<Message<Ds18b20>>(&payload)?;
-                            ds18b20.insert_one(message, None).await?;
+                            let message = parse::<Ds18b20>(&payload)?;
+                            collections.ds18b20.insert_one(message, None).await?;
                         }
                         MAX3010X => {
-                            let message = serde_json::from_str::<Message<Max3010x>>(&payload)?;
-                            max3010x.insert_one(message, None).await?;
+                            let message = parse::<Max3010x>(&payload)?;
+                            collections.max3010x.insert_one(message, None).await?;
                         }
                         MPU6050 => {
-                            let message = serde_json::from_str::<Message<Mpu6050>>(&payload)?;
-                            mpu6050.insert_one(message, None).await?;
+                            let message = parse::<Mpu6050>(&payload)?;
+                            collections.mpu6050.insert_one(message, None).await?;
                         }
                         REPORT => {
-                            let message = serde_json::from_str::<Message<Report>>(&payload)?;
-                            report.insert_one(message, None).await?;
+                            let message = parse::<Report>(&payload)?;
+                            collections.report.insert_one(&message, None).await?;
+
+                            // Urgent reports reach the dashboard even when the
+                            // device's live copy was lost.
+                            if message.payload.priority == Priority::High {
+                                if let Some(tx) = txs.get(REPORT) {
+                                    let _ = tx.send(serde_json::to_string(&message)?);
+                                }
+                            }
+                        }
+                        ACTIVITY => {
+                            let message = parse::<Activity>(&payload)?;
+                            collections.activity.insert_one(message, None).await?;
+                        }
+                        HRV => {
+                            let message = parse::<Hrv>(&payload)?;
+                            collections.hrv.insert_one(message, None).await?;
+                        }
+                        TELEMETRY => {
+                            let message = parse::<Telemetry>(&payload)?;
+                            handle_telemetry(message, &txs, collections).await?;
                         }
                         _ => {}
                     }
@@ -79,11 +194,8 @@ pub async fn handle(
 }
 
 pub async fn init(
-    ds18b20: Collection<Message<Ds18b20>>,
-    max3010x: Collection<Message<Max3010x>>,
-    mpu6050: Collection<Message<Mpu6050>>,
-    report: Collection<Message<Report>>,
-) -> Result<Arc<HashMap<String, Sender<String>>>> {
+    collections: Collections,
+) -> Result<(Arc<HashMap<String, Sender<String>>>, AsyncClient)> {
     let mut mqttoptions = MqttOptions::new(CLIENT_ID, HOST, PORT.parse::<u16>()?);
     mqttoptions.set_keep_alive(Duration::from_secs(5));
 
@@ -91,13 +203,27 @@ pub async fn init(
     let mut txs = HashMap::new();
 
     for &update in RED_UPDATES.iter() {
+        // Stored readings keep the at least once delivery the devices publish
+        // them with, live ones are stale by the time a retry arrives.
+        let qos = match update {
+            DATABASE => QoS::AtLeastOnce,
+            _ => QoS::AtMostOnce,
+        };
+
         for &driver in PAYLOADS.iter() {
             client
-                .subscribe(&format!("{}/{}", update, driver), QoS::AtMostOnce)
+                .subscribe(&format!("{}/{}", update, driver), qos)
                 .await?;
         }
     }
 
+    client
+        .subscribe(format!("{}/+", CONFIG), QoS::AtLeastOnce)
+        .await?;
+    client
+        .subscribe(format!("{}/+", LOGS), QoS::AtLeastOnce)
+        .await?;
+
     for &driver in PAYLOADS.iter() {
         let (tx, _) = broadcast::channel(10);
         txs.insert(driver.to_string(), tx);
@@ -105,20 +231,12 @@ pub async fn init(
 
     let txs = Arc::new(txs);
     let txs_clone = Arc::clone(&txs);
+    let publisher = client.clone();
 
     task::spawn(async move {
         while let Ok(event) = eventloop.poll().await {
             if let Event::Incoming(Incoming::Publish(publish)) = event {
-                if let Err(e) = handle(
-                    &publish,
-                    Arc::clone(&txs_clone),
-                    &ds18b20,
-                    &max3010x,
-                    &mpu6050,
-                    &report,
-                )
-                .await
-                {
+                if let Err(e) = handle(&publish, Arc::clone(&txs_clone), &collections).await {
                     println!("Error: {}", e);
                 }
 
@@ -132,5 +250,44 @@ pub async fn init(
         }
     });
 
-    Ok(txs)
+    Ok((txs, publisher))
+}
+
+async fn publish_command<T: Serialize>(
+    client: &AsyncClient,
+    device: &str,
+    name: &str,
+    value: &T,
+    retain: bool,
+) -> Result<()> {
+    client
+        .publish(
+            format!("{}/{}/{}", COMMAND, device, name),
+            QoS::AtLeastOnce,
+            retain,
+            serde_json::to_string(value)?,
+        )
+        .await?;
+
+    Ok(())
+}
+
+pub async fn send_command<T: Serialize>(
+    client: &AsyncClient,
+    device: &str,
+    name: &str,
+    value: &T,
+) -> Result<()> {
+    publish_command(client, device, name, value, false).await
+}
+
+/// Sends a command the broker keeps, so a device that is offline picks it up
+/// as soon as it subscribes again.
+pub async fn send_retained<T: Serialize>(
+    client: &AsyncClient,
+    device: &str,
+    name: &str,
+    value: &T,
+) -> Result<()> {
+    publish_command(client, device, name, value, true).await
 }
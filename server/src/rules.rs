@@ -0,0 +1,80 @@
+use crate::messages::{Priority, Report, Telemetry};
+
+pub const LOW_BATTERY: u8 = 15;
+pub const BATTERY: &str = "battery";
+
+/// Reports a low battery once, when a reading first drops to the threshold,
+/// instead of on every reading below it.
+pub fn low_battery(previous: Option<&Telemetry>, current: &Telemetry) -> Option<Report> {
+    let battery = current.battery?;
+    let was_low = previous
+        .and_then(|previous| previous.battery)
+        .is_some_and(|previous| previous <= LOW_BATTERY);
+
+    if battery > LOW_BATTERY || was_low {
+        return None;
+    }
+
+    Some(Report {
+        status: BATTERY.to_string(),
+        description: format!("Battery low: {}%", battery),
+        priority: Priority::Normal,
+        vitals: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn telemetry(battery: Option<u8>) -> Telemetry {
+        Telemetry {
+            device: "band".to_string(),
+            voltage: None,
+            battery,
+            rssi: None,
+            uptime: 0,
+            heap: 0,
+            min_heap: 0,
+            queue: 0,
+            errors: Default::default(),
+        }
+    }
+
+    #[test]
+    fn reports_once_the_battery_reaches_the_threshold() {
+        let previous = telemetry(Some(LOW_BATTERY + 1));
+
+        assert!(low_battery(Some(&previous), &telemetry(Some(LOW_BATTERY + 1))).is_none());
+
+        let report = low_battery(Some(&previous), &telemetry(Some(LOW_BATTERY))).unwrap();
+        assert_eq!(report.status, BATTERY);
+        assert_eq!(report.description, format!("Battery low: {}%", LOW_BATTERY));
+    }
+
+    #[test]
+    fn reports_a_low_first_reading() {
+        assert!(low_battery(None, &telemetry(Some(5))).is_some());
+        assert!(low_battery(Some(&telemetry(None)), &telemetry(Some(5))).is_some());
+    }
+
+    #[test]
+    fn does_not_report_a_battery_already_reported() {
+        let previous = telemetry(Some(LOW_BATTERY));
+
+        assert!(low_battery(Some(&previous), &telemetry(Some(LOW_BATTERY - 1))).is_none());
+        assert!(low_battery(Some(&previous), &telemetry(Some(0))).is_none());
+    }
+
+    #[test]
+    fn reports_again_after_charging() {
+        let charged = telemetry(Some(80));
+
+        assert!(low_battery(Some(&charged), &telemetry(Some(10))).is_some());
+    }
+
+    #[test]
+    fn ignores_an_unknown_level() {
+        assert!(low_battery(Some(&telemetry(Some(50))), &telemetry(None)).is_none());
+    }
+}
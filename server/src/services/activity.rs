@@ -0,0 +1,87 @@
+use crate::messages::{Activity, Message};
+use crate::utils;
+use actix_web::{post, web, HttpResponse, Responder, Result};
+use mongodb::{
+    bson::{self, doc, Bson},
+    Collection,
+};
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Group {
+    pub day: f64,
+    pub activity: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Value {
+    pub _id: Group,
+    pub duration: f64,
+    pub calories: f64,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Data {
+    pub day: i64,
+    pub activity: String,
+    pub duration: f64,
+    pub calories: f64,
+}
+
+#[post("/activity")]
+pub async fn get_values(
+    data: web::Data<Collection<Message<Activity>>>,
+    req_body: String,
+) -> Result<impl Responder, Box<dyn Error>> {
+    let req = serde_json::from_str::<utils::Request>(&req_body)?;
+    let start = utils::get_midnight(utils::get_days(&req.unit));
+
+    let mut raw = data
+        .aggregate(
+            [
+                doc! {
+                    "$match": {
+                        "headers.timestamp": { "$gte": start }
+                    }
+                },
+                doc! {
+                    "$group": {
+                        "_id": {
+                            "day": {
+                                "$floor": {
+                                    "$divide": [
+                                        { "$subtract": [ "$headers.timestamp", start ] },
+                                        utils::DAY
+                                    ]
+                                }
+                            },
+                            "activity": "$payload.activity"
+                        },
+                        "duration": { "$sum": "$payload.duration" },
+                        "calories": { "$sum": "$payload.calories" }
+                    }
+                },
+                doc! {
+                    "$sort": { "_id.day": 1, "_id.activity": 1 }
+                },
+            ],
+            None,
+        )
+        .await?;
+
+    let mut values = Vec::new();
+
+    while raw.advance().await? {
+        let value: Value = bson::from_bson(Bson::Document(raw.deserialize_current()?))?;
+
+        values.push(Data {
+            day: start + value._id.day as i64 * utils::DAY,
+            activity: value._id.activity,
+            duration: value.duration,
+            calories: value.calories,
+        });
+    }
+
+    Ok(HttpResponse::Ok().body(serde_json::to_string(&values)?))
+}
@@ -0,0 +1,52 @@
+use crate::mqtt;
+use actix_web::{post, web, HttpResponse, Responder, Result};
+use rumqttc::v5::AsyncClient;
+use serde::{Deserialize, Serialize};
+use std::{error::Error, ops::RangeInclusive};
+
+pub const ALARM: &str = "alarm";
+
+// What a DS18B20 can measure.
+const TEMPERATURE: RangeInclusive<f32> = -55.0..=125.0;
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Request {
+    pub device: String,
+    #[serde(default)]
+    pub rom: Option<String>,
+    pub high: f32,
+    pub low: f32,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Command {
+    pub rom: Option<String>,
+    pub high: f32,
+    pub low: f32,
+}
+
+/// Sets the calibrated temperatures outside which a probe raises a report.
+#[post("/alarm")]
+pub async fn set_values(
+    client: web::Data<AsyncClient>,
+    req_body: String,
+) -> Result<impl Responder, Box<dyn Error>> {
+    let req = serde_json::from_str::<Request>(&req_body)?;
+
+    if !TEMPERATURE.contains(&req.high) || !TEMPERATURE.contains(&req.low) {
+        return Ok(HttpResponse::BadRequest().body("thresholds out of range"));
+    }
+
+    if req.low >= req.high {
+        return Ok(HttpResponse::BadRequest().body("low must be below high"));
+    }
+
+    let command = Command {
+        rom: req.rom.clone(),
+        high: req.high,
+        low: req.low,
+    };
+    mqtt::send_retained(&client, &req.device, ALARM, &command).await?;
+
+    Ok(HttpResponse::Ok().body(serde_json::to_string(&req)?))
+}
@@ -0,0 +1,38 @@
+use crate::messages::Calibration;
+use crate::mqtt;
+use actix_web::{post, web, HttpResponse, Responder, Result};
+use rumqttc::v5::AsyncClient;
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+
+pub const CALIBRATION: &str = "calibration";
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Request {
+    pub device: String,
+    #[serde(default)]
+    pub rom: Option<String>,
+    pub calibration: Calibration,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Command {
+    pub rom: Option<String>,
+    #[serde(flatten)]
+    pub calibration: Calibration,
+}
+
+#[post("/calibration")]
+pub async fn set_values(
+    client: web::Data<AsyncClient>,
+    req_body: String,
+) -> Result<impl Responder, Box<dyn Error>> {
+    let req = serde_json::from_str::<Request>(&req_body)?;
+    let command = Command {
+        rom: req.rom.clone(),
+        calibration: req.calibration,
+    };
+    mqtt::send_retained(&client, &req.device, CALIBRATION, &command).await?;
+
+    Ok(HttpResponse::Ok().body(serde_json::to_string(&req)?))
+}
@@ -0,0 +1,44 @@
+use crate::messages::DeviceConfig;
+use crate::mqtt::{self, CONFIG};
+use actix_web::{get, post, web, HttpResponse, Responder, Result};
+use mongodb::{bson::doc, Collection};
+use rumqttc::v5::AsyncClient;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::error::Error;
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Request {
+    pub device: String,
+    pub config: Value,
+}
+
+#[post("/config")]
+pub async fn set_values(
+    client: web::Data<AsyncClient>,
+    req_body: String,
+) -> Result<impl Responder, Box<dyn Error>> {
+    let req = serde_json::from_str::<Request>(&req_body)?;
+
+    if !req.config.is_object() {
+        return Ok(HttpResponse::BadRequest().body("config must be an object"));
+    }
+
+    mqtt::send_retained(&client, &req.device, CONFIG, &req.config).await?;
+
+    Ok(HttpResponse::Ok().body(serde_json::to_string(&req)?))
+}
+
+#[get("/config/{device}")]
+pub async fn get_values(
+    data: web::Data<Collection<DeviceConfig>>,
+    device: web::Path<String>,
+) -> Result<impl Responder, Box<dyn Error>> {
+    match data
+        .find_one(doc! { "device": device.into_inner() }, None)
+        .await?
+    {
+        Some(config) => Ok(HttpResponse::Ok().body(serde_json::to_string(&config)?)),
+        None => Ok(HttpResponse::NotFound().finish()),
+    }
+}
@@ -0,0 +1,42 @@
+use crate::mqtt;
+use actix_web::{post, web, HttpResponse, Responder, Result};
+use rumqttc::v5::AsyncClient;
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+
+pub const HAPTICS: &str = "haptics";
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum Pattern {
+    Tick,
+    Double,
+    Alarm,
+    Heartbeat,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Request {
+    pub device: String,
+    pub pattern: Pattern,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Command {
+    pub pattern: Pattern,
+}
+
+/// Buzzes a device, for instance to find it.
+#[post("/haptics")]
+pub async fn play(
+    client: web::Data<AsyncClient>,
+    req_body: String,
+) -> Result<impl Responder, Box<dyn Error>> {
+    let req = serde_json::from_str::<Request>(&req_body)?;
+    let command = Command {
+        pattern: req.pattern,
+    };
+    mqtt::send_command(&client, &req.device, HAPTICS, &command).await?;
+
+    Ok(HttpResponse::Ok().body(serde_json::to_string(&req)?))
+}
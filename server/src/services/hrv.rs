@@ -0,0 +1,36 @@
+use crate::messages::{Hrv, Message};
+use crate::utils;
+use actix_web::{post, web, HttpResponse, Responder, Result};
+use mongodb::Collection;
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Metrics {
+    pub rmssd: Vec<utils::Data>,
+    pub sdnn: Vec<utils::Data>,
+    pub pnn50: Vec<utils::Data>,
+}
+
+#[post("/hrv")]
+pub async fn get_values(
+    data: web::Data<Collection<Message<Hrv>>>,
+    req_body: String,
+) -> Result<impl Responder, Box<dyn Error>> {
+    let req = serde_json::from_str::<utils::Request>(&req_body)?;
+
+    let fragment = utils::get_fragment(&req.unit);
+    let start = utils::get_start(&fragment);
+
+    let mut rmssd = utils::get_range_average(&data, "$payload.rmssd", &start, &fragment).await?;
+    let mut sdnn = utils::get_range_average(&data, "$payload.sdnn", &start, &fragment).await?;
+    let mut pnn50 = utils::get_range_average(&data, "$payload.pnn50", &start, &fragment).await?;
+
+    let metrics = Metrics {
+        rmssd: utils::normalize(&mut rmssd, &fragment, start),
+        sdnn: utils::normalize(&mut sdnn, &fragment, start),
+        pnn50: utils::normalize(&mut pnn50, &fragment, start),
+    };
+
+    Ok(HttpResponse::Ok().body(serde_json::to_string(&metrics)?))
+}
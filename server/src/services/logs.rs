@@ -0,0 +1,67 @@
+use crate::messages::Log;
+use actix_web::{post, web, HttpResponse, Responder, Result};
+use mongodb::{bson::doc, options::FindOptions, Collection};
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+
+const DEFAULT_LIMIT: i64 = 100;
+const MAX_LIMIT: i64 = 1000;
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Request {
+    #[serde(default)]
+    pub device: Option<String>,
+    #[serde(default)]
+    pub level: Option<String>,
+    #[serde(default)]
+    pub start: Option<i64>,
+    #[serde(default)]
+    pub end: Option<i64>,
+    #[serde(default)]
+    pub limit: Option<i64>,
+}
+
+#[post("/logs")]
+pub async fn get_values(
+    data: web::Data<Collection<Log>>,
+    req_body: String,
+) -> Result<impl Responder, Box<dyn Error>> {
+    let req = serde_json::from_str::<Request>(&req_body)?;
+    let mut filter = doc! {};
+
+    if let Some(device) = &req.device {
+        filter.insert("device", device);
+    }
+
+    if let Some(level) = &req.level {
+        filter.insert("level", level.to_lowercase());
+    }
+
+    let mut range = doc! {};
+
+    if let Some(start) = req.start {
+        range.insert("$gte", start);
+    }
+
+    if let Some(end) = req.end {
+        range.insert("$lt", end);
+    }
+
+    if !range.is_empty() {
+        filter.insert("headers.timestamp", range);
+    }
+
+    let options = FindOptions::builder()
+        .sort(doc! { "headers.timestamp": -1 })
+        .limit(req.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT))
+        .build();
+
+    let mut cursor = data.find(filter, options).await?;
+    let mut logs = Vec::new();
+
+    while cursor.advance().await? {
+        logs.push(cursor.deserialize_current()?);
+    }
+
+    Ok(HttpResponse::Ok().body(serde_json::to_string(&logs)?))
+}
@@ -1,4 +1,13 @@
 pub mod temperature;
 pub mod report;
 pub mod steps;
-pub mod heart_rate;
\ No newline at end of file
+pub mod heart_rate;
+pub mod activity;
+pub mod sleep;
+pub mod hrv;
+pub mod calibration;
+pub mod config;
+pub mod alarm;
+pub mod logs;
+pub mod telemetry;
+pub mod haptics;
@@ -0,0 +1,26 @@
+use crate::sleep::Session;
+use crate::utils;
+use actix_web::{post, web, HttpResponse, Responder, Result};
+use mongodb::{bson::doc, options::FindOptions, Collection};
+use std::error::Error;
+
+#[post("/sleep")]
+pub async fn get_values(
+    data: web::Data<Collection<Session>>,
+    req_body: String,
+) -> Result<impl Responder, Box<dyn Error>> {
+    let req = serde_json::from_str::<utils::Request>(&req_body)?;
+    let start = utils::get_midnight(utils::get_days(&req.unit));
+
+    let filter = doc! { "onset": { "$gte": start - utils::DAY } };
+    let options = FindOptions::builder().sort(doc! { "onset": 1 }).build();
+
+    let mut cursor = data.find(filter, options).await?;
+    let mut sessions = Vec::new();
+
+    while cursor.advance().await? {
+        sessions.push(cursor.deserialize_current()?);
+    }
+
+    Ok(HttpResponse::Ok().body(serde_json::to_string(&sessions)?))
+}
@@ -0,0 +1,22 @@
+use crate::messages::{Message, Telemetry};
+use actix_web::{get, web, HttpResponse, Responder, Result};
+use mongodb::{bson::doc, options::FindOneOptions, Collection};
+use std::error::Error;
+
+#[get("/telemetry/{device}")]
+pub async fn get_values(
+    data: web::Data<Collection<Message<Telemetry>>>,
+    device: web::Path<String>,
+) -> Result<impl Responder, Box<dyn Error>> {
+    let latest = FindOneOptions::builder()
+        .sort(doc! { "headers.timestamp": -1 })
+        .build();
+
+    match data
+        .find_one(doc! { "payload.device": device.into_inner() }, latest)
+        .await?
+    {
+        Some(telemetry) => Ok(HttpResponse::Ok().body(serde_json::to_string(&telemetry)?)),
+        None => Ok(HttpResponse::NotFound().finish()),
+    }
+}
@@ -1,9 +1,9 @@
 use crate::messages::{Ds18b20, Message};
 use crate::utils;
-use actix_web::{post, web, Either, HttpResponse, Responder, Result};
+use actix_web::{get, post, web, Either, HttpResponse, Responder, Result};
 use lazy_static::lazy_static;
 use mongodb::{
-    bson::{self, doc, Bson},
+    bson::{self, doc, Bson, Document},
     Collection,
 };
 use serde::{Deserialize, Serialize};
@@ -18,9 +18,28 @@ pub async fn get_values(
 
     let fragment = utils::get_fragment(&req.unit);
     let start = utils::get_start(&fragment);
+    let filter = match &req.rom {
+        Some(rom) => doc! { "payload.rom": rom },
+        None => Document::new(),
+    };
     let mut messages =
-        utils::get_range_average(&data, "$payload.temperature", &start, &fragment).await?;
+        utils::get_range_average_by(&data, "$payload.temperature", &start, &fragment, filter)
+            .await?;
     let values = utils::normalize(&mut messages, &fragment, start);
 
     Ok(HttpResponse::Ok().body(serde_json::to_string(&values)?))
-}
\ No newline at end of file
+}
+
+#[get("/temperature/probes")]
+pub async fn get_probes(
+    data: web::Data<Collection<Message<Ds18b20>>>,
+) -> Result<impl Responder, Box<dyn Error>> {
+    let probes = data
+        .distinct("payload.rom", doc! { "payload.rom": { "$ne": "" } }, None)
+        .await?
+        .into_iter()
+        .filter_map(|rom| rom.as_str().map(|rom| rom.to_string()))
+        .collect::<Vec<_>>();
+
+    Ok(HttpResponse::Ok().body(serde_json::to_string(&probes)?))
+}
@@ -0,0 +1,513 @@
+use crate::messages::{Activity, Max3010x, Message};
+use anyhow::Result;
+use chrono::{Duration as ChronoDuration, Local, NaiveDate, NaiveDateTime, TimeZone, Timelike};
+use mongodb::{
+    bson::doc,
+    options::{FindOneOptions, ReplaceOptions},
+    Collection,
+};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tokio::task;
+
+pub const SLEEP: &str = "sleep";
+
+const EPOCH: i64 = 60;
+const NIGHT_START_HOUR: u32 = 20;
+const NIGHT_END_HOUR: u32 = 12;
+const JOB_INTERVAL: Duration = Duration::from_secs(60 * 60);
+// How far back the job looks for nights it has not analysed yet.
+const MAX_BACKFILL_DAYS: i64 = 14;
+
+const WEIGHTS: [f64; 7] = [0.106, 0.054, 0.058, 0.076, 0.230, 0.074, 0.067];
+const CENTER: usize = 4;
+const SLEEP_THRESHOLD: f64 = 0.03;
+const DEEP_THRESHOLD: f64 = 0.012;
+const DEEP_HEART_RATE_PERCENTILE: f64 = 0.35;
+const MIN_SLEEP_RUN: usize = 10;
+const MIN_WAKE_RUN: usize = 30;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Stage {
+    Wake,
+    Light,
+    Deep,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Phase {
+    pub stage: Stage,
+    pub start: i64,
+    pub end: i64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Session {
+    pub night: String,
+    pub onset: i64,
+    pub wake: i64,
+    pub duration: i64,
+    pub efficiency: f64,
+    pub wake_minutes: i64,
+    pub light_minutes: i64,
+    pub deep_minutes: i64,
+    pub phases: Vec<Phase>,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Epoch {
+    pub motion: Option<f64>,
+    pub heart_rate: Option<f64>,
+}
+
+pub fn build_epochs(
+    start: i64,
+    end: i64,
+    motion: &[(i64, f64)],
+    heart_rate: &[(i64, f64)],
+) -> Vec<Epoch> {
+    let len = ((end - start) / EPOCH).max(0) as usize;
+    let mut sums = vec![(0.0, 0, 0.0, 0); len];
+
+    for &(timestamp, value) in motion {
+        if let Some(sum) = sums.get_mut(((timestamp - start) / EPOCH) as usize) {
+            sum.0 += value;
+            sum.1 += 1;
+        }
+    }
+
+    for &(timestamp, value) in heart_rate {
+        if let Some(sum) = sums.get_mut(((timestamp - start) / EPOCH) as usize) {
+            sum.2 += value;
+            sum.3 += 1;
+        }
+    }
+
+    sums.into_iter()
+        .map(|(motion, motions, heart_rate, heart_rates)| Epoch {
+            motion: (motions > 0).then(|| motion / motions as f64),
+            heart_rate: (heart_rates > 0).then(|| heart_rate / heart_rates as f64),
+        })
+        .collect()
+}
+
+fn score(epochs: &[Epoch], i: usize) -> Option<f64> {
+    epochs[i].motion?;
+
+    let mut total = 0.0;
+    let mut weights = 0.0;
+
+    for (j, weight) in WEIGHTS.iter().enumerate() {
+        if let Some(motion) = (i + j)
+            .checked_sub(CENTER)
+            .and_then(|k| epochs.get(k))
+            .and_then(|epoch| epoch.motion)
+        {
+            total += weight * motion;
+            weights += weight;
+        }
+    }
+
+    Some(total / weights)
+}
+
+fn percentile(values: &mut [f64], fraction: f64) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+
+    values.sort_by(|a, b| a.total_cmp(b));
+    Some(values[((values.len() - 1) as f64 * fraction).round() as usize])
+}
+
+fn find_run(asleep: &[bool], value: bool, run: usize, from: usize) -> Option<usize> {
+    let mut count = 0;
+
+    for (i, &current) in asleep.iter().enumerate().skip(from) {
+        count = if current == value { count + 1 } else { 0 };
+
+        if count >= run {
+            return Some(i + 1 - run);
+        }
+    }
+
+    None
+}
+
+pub fn analyze(night: &str, start: i64, epochs: &[Epoch]) -> Option<Session> {
+    let scores = (0..epochs.len())
+        .map(|i| score(epochs, i))
+        .collect::<Vec<_>>();
+    let asleep = scores
+        .iter()
+        .map(|score| score.is_some_and(|score| score < SLEEP_THRESHOLD))
+        .collect::<Vec<_>>();
+
+    let onset = find_run(&asleep, true, MIN_SLEEP_RUN, 0)?;
+    let wake = find_run(&asleep, false, MIN_WAKE_RUN, onset).unwrap_or(asleep.len());
+    let wake = asleep[onset..wake].iter().rposition(|&a| a)? + onset + 1;
+
+    let mut heart_rates = epochs[onset..wake]
+        .iter()
+        .filter_map(|epoch| epoch.heart_rate)
+        .collect::<Vec<_>>();
+    let deep_heart_rate = percentile(&mut heart_rates, DEEP_HEART_RATE_PERCENTILE);
+
+    let mut phases: Vec<Phase> = Vec::new();
+    let (mut wake_minutes, mut light_minutes, mut deep_minutes) = (0, 0, 0);
+
+    for i in onset..wake {
+        let calm = scores[i].is_some_and(|score| score < DEEP_THRESHOLD);
+        let slow = match (epochs[i].heart_rate, deep_heart_rate) {
+            (Some(heart_rate), Some(deep)) => heart_rate <= deep,
+            _ => true,
+        };
+
+        let stage = if !asleep[i] {
+            wake_minutes += 1;
+            Stage::Wake
+        } else if calm && slow {
+            deep_minutes += 1;
+            Stage::Deep
+        } else {
+            light_minutes += 1;
+            Stage::Light
+        };
+
+        let epoch_start = start + i as i64 * EPOCH;
+
+        match phases.last_mut() {
+            Some(phase) if phase.stage == stage => phase.end = epoch_start + EPOCH,
+            _ => phases.push(Phase {
+                stage,
+                start: epoch_start,
+                end: epoch_start + EPOCH,
+            }),
+        }
+    }
+
+    let minutes = (wake - onset) as i64;
+
+    Some(Session {
+        night: night.to_string(),
+        onset: start + onset as i64 * EPOCH,
+        wake: start + wake as i64 * EPOCH,
+        duration: (light_minutes + deep_minutes) * EPOCH,
+        efficiency: (light_minutes + deep_minutes) as f64 / minutes as f64,
+        wake_minutes,
+        light_minutes,
+        deep_minutes,
+        phases,
+    })
+}
+
+pub fn get_night(date: NaiveDate) -> Option<(i64, i64)> {
+    let start = date.and_hms_opt(NIGHT_START_HOUR, 0, 0)?;
+    let end = (date + ChronoDuration::days(1)).and_hms_opt(NIGHT_END_HOUR, 0, 0)?;
+
+    Some((
+        Local.from_local_datetime(&start).earliest()?.timestamp(),
+        Local.from_local_datetime(&end).earliest()?.timestamp(),
+    ))
+}
+
+async fn find<T, F>(
+    collection: &Collection<Message<T>>,
+    start: i64,
+    end: i64,
+    value: F,
+) -> Result<Vec<(i64, f64)>>
+where
+    T: serde::de::DeserializeOwned + Unpin + Send + Sync,
+    F: Fn(&T) -> Option<f64>,
+{
+    let filter = doc! {
+        "headers.timestamp": { "$gte": start, "$lt": end }
+    };
+
+    let mut cursor = collection.find(filter, None).await?;
+    let mut values = Vec::new();
+
+    while cursor.advance().await? {
+        let message = cursor.deserialize_current()?;
+
+        if let Some(v) = value(&message.payload) {
+            values.push((message.headers.timestamp, v));
+        }
+    }
+
+    Ok(values)
+}
+
+pub async fn process(
+    date: NaiveDate,
+    activity: &Collection<Message<Activity>>,
+    max3010x: &Collection<Message<Max3010x>>,
+    sleep: &Collection<Session>,
+) -> Result<Option<Session>> {
+    let night = date.format("%Y-%m-%d").to_string();
+    let (start, end) = get_night(date).ok_or_else(|| anyhow::anyhow!("Invalid night {}", night))?;
+
+    let motion = find(activity, start, end, |a| Some(a.intensity as f64)).await?;
+    let heart_rate = find(max3010x, start, end, |m| {
+        (m.heart_rate > 0).then_some(m.heart_rate as f64)
+    })
+    .await?;
+
+    let epochs = build_epochs(start, end, &motion, &heart_rate);
+    let session = analyze(&night, start, &epochs);
+
+    if let Some(session) = &session {
+        sleep
+            .replace_one(
+                doc! { "night": &night },
+                session,
+                ReplaceOptions::builder().upsert(true).build(),
+            )
+            .await?;
+    }
+
+    Ok(session)
+}
+
+/// The last night that has ended at `now`.
+pub fn latest_night(now: NaiveDateTime) -> NaiveDate {
+    let days = if now.hour() >= NIGHT_END_HOUR { 1 } else { 2 };
+    now.date() - ChronoDuration::days(days)
+}
+
+/// The nights after `last` up to `latest`, going back at most
+/// `MAX_BACKFILL_DAYS`.
+pub fn pending(last: Option<NaiveDate>, latest: NaiveDate) -> Vec<NaiveDate> {
+    let oldest = latest - ChronoDuration::days(MAX_BACKFILL_DAYS - 1);
+    let first = match last {
+        Some(last) => (last + ChronoDuration::days(1)).max(oldest),
+        None => oldest,
+    };
+
+    first
+        .iter_days()
+        .take_while(|date| *date <= latest)
+        .collect()
+}
+
+async fn last_stored(sleep: &Collection<Session>) -> Result<Option<NaiveDate>> {
+    let options = FindOneOptions::builder().sort(doc! { "night": -1 }).build();
+
+    Ok(sleep
+        .find_one(None, options)
+        .await?
+        .and_then(|session| NaiveDate::parse_from_str(&session.night, "%Y-%m-%d").ok()))
+}
+
+pub fn init(
+    activity: Collection<Message<Activity>>,
+    max3010x: Collection<Message<Max3010x>>,
+    sleep: Collection<Session>,
+) {
+    task::spawn(async move {
+        // Nights without a session are not stored, so this keeps them from
+        // being analysed again every hour.
+        let mut processed: Option<NaiveDate> = None;
+
+        loop {
+            let latest = latest_night(Local::now().naive_local());
+
+            match last_stored(&sleep).await {
+                Ok(last) => {
+                    for date in pending(last.max(processed), latest) {
+                        match process(date, &activity, &max3010x, &sleep).await {
+                            Ok(Some(session)) => println!("SLEEP => {:?}", session.night),
+                            Ok(None) => println!("SLEEP => no session for {}", date),
+                            Err(e) => {
+                                println!("Error: {}", e);
+                                break;
+                            }
+                        }
+
+                        processed = Some(date);
+                    }
+                }
+                Err(e) => println!("Error: {}", e),
+            }
+
+            tokio::time::sleep(JOB_INTERVAL).await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const START: i64 = 1_700_000_000;
+
+    fn epochs(motion: &[(usize, f64)]) -> Vec<Epoch> {
+        motion
+            .iter()
+            .flat_map(|&(len, value)| {
+                std::iter::repeat_n(
+                    Epoch {
+                        motion: Some(value),
+                        heart_rate: None,
+                    },
+                    len,
+                )
+            })
+            .collect()
+    }
+
+    fn date(value: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn builds_epoch_averages() {
+        let motion = [
+            (START, 1.0),
+            (START + 30, 3.0),
+            (START + 120, 5.0),
+            (START + 600, 9.0),
+        ];
+        let heart_rate = [(START + 61, 60.0)];
+        let epochs = build_epochs(START, START + 180, &motion, &heart_rate);
+
+        assert_eq!(epochs.len(), 3);
+        assert_eq!(epochs[0].motion, Some(2.0));
+        assert_eq!(epochs[0].heart_rate, None);
+        assert_eq!(epochs[1].motion, None);
+        assert_eq!(epochs[1].heart_rate, Some(60.0));
+        assert_eq!(epochs[2].motion, Some(5.0));
+    }
+
+    #[test]
+    fn scores_only_epochs_with_motion() {
+        let mut epochs = epochs(&[(10, 0.1)]);
+        epochs[5].motion = None;
+
+        assert_eq!(score(&epochs, 5), None);
+
+        // Missing neighbours drop out of the weighting instead of counting as still.
+        for i in [0, 4, 6, 9] {
+            let score = score(&epochs, i).unwrap();
+            assert!((score - 0.1).abs() < 1e-9, "epoch {}: {}", i, score);
+        }
+    }
+
+    #[test]
+    fn weights_the_centre_epoch_most() {
+        let mut epochs = epochs(&[(9, 0.0)]);
+        epochs[4].motion = Some(1.0);
+
+        let centre = score(&epochs, 4).unwrap();
+        let before = score(&epochs, 3).unwrap();
+        let after = score(&epochs, 5).unwrap();
+
+        assert!(centre > before && centre > after);
+    }
+
+    #[test]
+    fn finds_the_night_between_waking_periods() {
+        let epochs = epochs(&[
+            (30, 0.5),
+            (120, 0.005),
+            (60, 0.02),
+            (120, 0.005),
+            (150, 0.5),
+        ]);
+        let session = analyze("2023-11-14", START, &epochs).unwrap();
+
+        // The window looks four epochs back and two ahead.
+        assert_eq!(session.onset, START + 34 * EPOCH);
+        assert_eq!(session.wake, START + 328 * EPOCH);
+        assert_eq!(session.wake_minutes, 0);
+        assert_eq!(session.efficiency, 1.0);
+        assert!(session.deep_minutes > 200);
+        assert!(session.light_minutes >= 60);
+        assert_eq!(
+            session.duration,
+            (session.light_minutes + session.deep_minutes) * EPOCH
+        );
+
+        assert_eq!(session.phases.first().unwrap().start, session.onset);
+        assert_eq!(session.phases.last().unwrap().end, session.wake);
+        assert!(session
+            .phases
+            .windows(2)
+            .all(|pair| pair[0].end == pair[1].start && pair[0].stage != pair[1].stage));
+    }
+
+    #[test]
+    fn keeps_slow_heart_rate_as_deep_sleep() {
+        let mut epochs = epochs(&[(20, 0.5), (200, 0.005), (50, 0.5)]);
+
+        for (i, epoch) in epochs.iter_mut().enumerate() {
+            epoch.heart_rate = Some(if i < 120 { 50.0 } else { 70.0 });
+        }
+
+        let session = analyze("2023-11-14", START, &epochs).unwrap();
+
+        assert!(session.deep_minutes > 0);
+        assert!(session.light_minutes > 0);
+        assert!(session
+            .phases
+            .iter()
+            .filter(|phase| phase.stage == Stage::Deep)
+            .all(|phase| phase.end <= START + 120 * EPOCH));
+    }
+
+    #[test]
+    fn counts_short_awakenings_inside_the_night() {
+        let epochs = epochs(&[(20, 0.5), (100, 0.005), (15, 0.5), (100, 0.005), (60, 0.5)]);
+        let session = analyze("2023-11-14", START, &epochs).unwrap();
+
+        assert!(session.wake_minutes > 0);
+        assert!(session.efficiency < 1.0);
+        assert!(session
+            .phases
+            .iter()
+            .any(|phase| phase.stage == Stage::Wake));
+    }
+
+    #[test]
+    fn needs_a_sustained_still_period() {
+        assert!(analyze("2023-11-14", START, &epochs(&[(300, 0.5)])).is_none());
+        assert!(analyze(
+            "2023-11-14",
+            START,
+            &epochs(&[(100, 0.5), (5, 0.0), (100, 0.5)])
+        )
+        .is_none());
+        assert!(analyze("2023-11-14", START, &[]).is_none());
+    }
+
+    #[test]
+    fn waits_for_the_night_to_end() {
+        let morning = date("2023-11-15")
+            .and_hms_opt(NIGHT_END_HOUR - 1, 59, 0)
+            .unwrap();
+        let noon = date("2023-11-15")
+            .and_hms_opt(NIGHT_END_HOUR, 0, 0)
+            .unwrap();
+
+        assert_eq!(latest_night(morning), date("2023-11-13"));
+        assert_eq!(latest_night(noon), date("2023-11-14"));
+    }
+
+    #[test]
+    fn backfills_the_nights_after_the_last_one() {
+        let latest = date("2023-11-14");
+
+        assert_eq!(
+            pending(Some(date("2023-11-11")), latest),
+            vec![date("2023-11-12"), date("2023-11-13"), date("2023-11-14")]
+        );
+        assert!(pending(Some(latest), latest).is_empty());
+        assert_eq!(pending(None, latest).len(), MAX_BACKFILL_DAYS as usize);
+        assert_eq!(
+            pending(Some(date("2020-01-01")), latest).first(),
+            Some(&(latest - ChronoDuration::days(MAX_BACKFILL_DAYS - 1)))
+        );
+    }
+}
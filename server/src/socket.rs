@@ -77,7 +77,7 @@ impl StreamHandler<Result<ws::Message, ws::ProtocolError>> for Session {
         let message = match msg {
             Ok(ws::Message::Ping(text)) => Some(String::from_utf8_lossy(&text).to_string()),
             Ok(ws::Message::Text(text)) => {
-                Some(String::from_utf8_lossy(&text.as_bytes()).to_string())
+                Some(String::from_utf8_lossy(text.as_bytes()).to_string())
             }
             Ok(ws::Message::Binary(bin)) => Some(String::from_utf8_lossy(&bin).to_string()),
             _ => None,
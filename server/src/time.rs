@@ -0,0 +1,35 @@
+use crate::messages::Offer;
+use anyhow::Result;
+use rumqttc::v5::{mqttbytes::QoS, AsyncClient};
+use std::time::Duration;
+use tokio::task;
+
+pub const TIME: &str = "time";
+
+const OFFER_INTERVAL: Duration = Duration::from_secs(30);
+
+async fn offer(client: &AsyncClient) -> Result<()> {
+    let offer = Offer {
+        unix_ms: chrono::Utc::now().timestamp_millis(),
+    };
+
+    // Not retained, a stale time would be worse than none.
+    client
+        .publish(TIME, QoS::AtMostOnce, false, serde_json::to_string(&offer)?)
+        .await?;
+
+    Ok(())
+}
+
+/// Offers the current time to devices that cannot reach an NTP server.
+pub fn init(client: AsyncClient) {
+    task::spawn(async move {
+        loop {
+            if let Err(e) = offer(&client).await {
+                println!("Error: {}", e);
+            }
+
+            tokio::time::sleep(OFFER_INTERVAL).await;
+        }
+    });
+}
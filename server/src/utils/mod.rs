@@ -1,14 +1,18 @@
+use chrono::{Local, TimeZone};
 use mongodb::{
-    bson::{self, doc, document, Bson},
+    bson::{self, doc, document, Bson, Document},
     Collection,
 };
 use serde::{Deserialize, Serialize};
 use std::{collections::HashSet, error::Error, time::Duration};
 
+pub const DAY: i64 = 60 * 60 * 24;
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct Request {
     pub unit: String,
+    #[serde(default)]
+    pub rom: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -39,22 +43,57 @@ pub fn get_start(fragment: &(u32, u32)) -> i64 {
         - Duration::from_secs(fragment.0 as u64 * fragment.1 as u64).as_secs() as i64
 }
 
+pub fn get_days(unit: &str) -> i64 {
+    match unit {
+        "week" => 7,
+        "month" => 30,
+        "year" => 365,
+        _ => 1,
+    }
+}
+
+pub fn get_midnight(days: i64) -> i64 {
+    let today = Local::now()
+        .date_naive()
+        .and_hms_opt(0, 0, 0)
+        .unwrap_or_default();
+    let midnight = Local
+        .from_local_datetime(&today)
+        .earliest()
+        .map_or(chrono::Utc::now().timestamp(), |date| date.timestamp());
+
+    midnight - DAY * (days - 1)
+}
+
 pub async fn get_range_average<T>(
     document: &Collection<T>,
     property: &str,
     start: &i64,
     fragment: &(u32, u32),
 ) -> Result<Vec<Value>, Box<dyn Error>> {
+    get_range_average_by(document, property, start, fragment, Document::new()).await
+}
+
+pub async fn get_range_average_by<T>(
+    document: &Collection<T>,
+    property: &str,
+    start: &i64,
+    fragment: &(u32, u32),
+    filter: Document,
+) -> Result<Vec<Value>, Box<dyn Error>> {
+    let mut matching = doc! {
+        "headers.timestamp": {
+            "$gte": start,
+            "$lte": chrono::Utc::now().timestamp()
+        }
+    };
+    matching.extend(filter);
+
     let mut raw = document
         .aggregate(
             [
                 doc! {
-                    "$match": {
-                        "headers.timestamp": {
-                            "$gte": start,
-                            "$lte": chrono::Utc::now().timestamp()
-                        }
-                    }
+                    "$match": matching
                 },
                 doc! {
                     "$group": {
@@ -89,7 +128,7 @@ pub async fn get_range_average<T>(
         }
     }
 
-    return Ok(messages);
+    Ok(messages)
 }
 
 pub fn normalize(messages: &mut Vec<Value>, fragment: &(u32, u32), start: i64) -> Vec<Data> {
@@ -112,7 +151,7 @@ pub fn normalize(messages: &mut Vec<Value>, fragment: &(u32, u32), start: i64) -
         if !ids.contains(&(id + 1)) {
             values.push(Data {
                 id: id as f32 + 1.0,
-                group: (fragment.0 * id as u32) + start as u32,
+                group: (fragment.0 * id) + start as u32,
                 average,
             });
         }
@@ -128,5 +167,5 @@ pub fn normalize(messages: &mut Vec<Value>, fragment: &(u32, u32), start: i64) -
         }
     });
 
-    return values;
+    values
 }